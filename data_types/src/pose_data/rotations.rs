@@ -2,7 +2,7 @@ use std::ops::{Add, Mul};
 
 use crate::{pose_data::{Vector2, Vector3}, prelude::Radian};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Rotation2D {
     // Angle in radians in range [0, 2π]
     pub angle: f64,
@@ -97,6 +97,7 @@ impl Mul<Rotation2D> for f64 {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rotation3D {
     // Unit quaternion representing orientation
     // w : scalar part
@@ -107,7 +108,15 @@ pub struct Rotation3D {
     pub z: f64,
 }
 
+impl Default for Rotation3D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
 impl Rotation3D {
+    pub const IDENTITY: Self = Self { w: 1.0, x: 0.0, y: 0.0, z: 0.0 };
+
     pub fn from_quaternion(w: f64, x: f64, y: f64, z: f64) -> Self {
         let norm_squared = w*w + x*x + y*y + z*z;
         if norm_squared == 0.0 {
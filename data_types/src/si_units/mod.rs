@@ -2,3 +2,6 @@ mod si_macros;
 
 mod si_unit_definitions;
 pub use self::si_unit_definitions::*;
+
+mod si_parsing;
+pub use self::si_parsing::*;
@@ -0,0 +1,235 @@
+use std::{fmt, str::FromStr};
+
+use typenum::Integer;
+
+use crate::si_units::SiValue;
+
+/// Exponents of kg, m, s, A, K, mol and cd.
+type Dimension = [i32; 7];
+
+const DIMENSION_NAMES: [&str; 7] = ["kg", "m", "s", "A", "K", "mol", "cd"];
+
+/// Error returned when parsing an [`SiValue`] from a string like "1.5 m" or "150 cm" fails.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitParseError {
+    InvalidNumber(String),
+    UnknownUnit(String),
+    /// The unit does not match the dimension of the value, e.g. "2 s" for a distance.
+    DimensionMismatch {
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for UnitParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitParseError::InvalidNumber(number) => write!(f, "invalid number '{}'", number),
+            UnitParseError::UnknownUnit(unit) => write!(f, "unknown unit '{}'", unit),
+            UnitParseError::DimensionMismatch { expected, found } => write!(f, "expected a value in [{}], found a value in [{}]", expected, found),
+        }
+    }
+}
+
+impl std::error::Error for UnitParseError {}
+
+/// Units accepted when parsing values: symbol, factor to the SI base unit, dimension and whether SI prefixes are allowed.
+const UNITS: &[(&str, f64, Dimension, bool)] = &[
+    ("m", 1.0, [0, 1, 0, 0, 0, 0, 0], true),
+    ("g", 1e-3, [1, 0, 0, 0, 0, 0, 0], true),
+    ("s", 1.0, [0, 0, 1, 0, 0, 0, 0], true),
+    ("min", 60.0, [0, 0, 1, 0, 0, 0, 0], false),
+    ("h", 3600.0, [0, 0, 1, 0, 0, 0, 0], false),
+    ("A", 1.0, [0, 0, 0, 1, 0, 0, 0], true),
+    ("K", 1.0, [0, 0, 0, 0, 1, 0, 0], true),
+    ("mol", 1.0, [0, 0, 0, 0, 0, 1, 0], true),
+    ("cd", 1.0, [0, 0, 0, 0, 0, 0, 1], true),
+    ("rad", 1.0, [0, 0, 0, 0, 0, 0, 0], true),
+    ("deg", std::f64::consts::PI / 180.0, [0, 0, 0, 0, 0, 0, 0], false),
+    ("°", std::f64::consts::PI / 180.0, [0, 0, 0, 0, 0, 0, 0], false),
+    ("Hz", 1.0, [0, 0, -1, 0, 0, 0, 0], true),
+    ("N", 1.0, [1, 1, -2, 0, 0, 0, 0], true),
+    ("Pa", 1.0, [1, -1, -2, 0, 0, 0, 0], true),
+    ("bar", 1e5, [1, -1, -2, 0, 0, 0, 0], true),
+    ("J", 1.0, [1, 2, -2, 0, 0, 0, 0], true),
+    ("W", 1.0, [1, 2, -3, 0, 0, 0, 0], true),
+    ("C", 1.0, [0, 0, 1, 1, 0, 0, 0], true),
+    ("V", 1.0, [1, 2, -3, -1, 0, 0, 0], true),
+    ("Ω", 1.0, [1, 2, -3, -2, 0, 0, 0], true),
+    ("ohm", 1.0, [1, 2, -3, -2, 0, 0, 0], true),
+    ("S", 1.0, [-1, -2, 3, 2, 0, 0, 0], true),
+    ("F", 1.0, [-1, -2, 4, 2, 0, 0, 0], true),
+    ("H", 1.0, [1, 2, -2, -2, 0, 0, 0], true),
+    ("Wb", 1.0, [1, 2, -2, -1, 0, 0, 0], true),
+    ("T", 1.0, [1, 0, -2, -1, 0, 0, 0], true),
+    ("lx", 1.0, [0, -2, 0, 0, 0, 0, 1], true),
+    ("L", 1e-3, [0, 3, 0, 0, 0, 0, 0], true),
+];
+
+const PREFIXES: &[(&str, f64)] = &[
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("u", 1e-6),
+    ("µ", 1e-6),
+    ("n", 1e-9),
+];
+
+/// Looks up a unit symbol with an optional SI prefix, e.g. "cm" or "kN".
+fn parse_symbol(symbol: &str) -> Option<(f64, Dimension)> {
+    if let Some((_, factor, dimension, _)) = UNITS.iter().find(|(name, ..)| *name == symbol) {
+        return Some((*factor, *dimension));
+    }
+    PREFIXES.iter().find_map(|(prefix, prefix_factor)| {
+        let unit = symbol.strip_prefix(prefix)?;
+        UNITS.iter()
+            .find(|(name, _, _, prefixable)| *prefixable && *name == unit)
+            .map(|(_, factor, dimension, _)| (prefix_factor * factor, *dimension))
+    })
+}
+
+/// Parses a factor of a unit like "m", "s^2", "s²" or "m^-1".
+fn parse_factor(factor: &str) -> Result<(f64, Dimension), UnitParseError> {
+    let unknown = || UnitParseError::UnknownUnit(factor.to_string());
+    let (symbol, exponent) = if let Some((symbol, exponent)) = factor.split_once('^') {
+        (symbol, exponent.parse::<i32>().map_err(|_| unknown())?)
+    } else if let Some(symbol) = factor.strip_suffix('²') {
+        (symbol, 2)
+    } else if let Some(symbol) = factor.strip_suffix('³') {
+        (symbol, 3)
+    } else {
+        (factor, 1)
+    };
+    let (scale, dimension) = parse_symbol(symbol).ok_or_else(unknown)?;
+    Ok((scale.powi(exponent), dimension.map(|exp| exp * exponent)))
+}
+
+/// Parses a unit like "km/h", "m/s^2" or "N·m" into the factor to SI base units and its dimension.
+/// Factors are separated by `*`, `·` or spaces, all factors after a `/` are in the denominator.
+fn parse_unit(unit: &str) -> Result<(f64, Dimension), UnitParseError> {
+    let mut scale = 1.0;
+    let mut dimension = [0; 7];
+    for (index, part) in unit.split('/').enumerate() {
+        if index > 1 {
+            return Err(UnitParseError::UnknownUnit(unit.to_string()));
+        }
+        let sign = if index == 0 { 1 } else { -1 };
+        for factor in part.split(['*', '·', ' ']).filter(|factor| !factor.is_empty()) {
+            let (factor_scale, factor_dimension) = parse_factor(factor)?;
+            scale *= factor_scale.powi(sign);
+            for (exp, factor_exp) in dimension.iter_mut().zip(factor_dimension) {
+                *exp += sign * factor_exp;
+            }
+        }
+    }
+    Ok((scale, dimension))
+}
+
+fn format_dimension(dimension: &Dimension) -> String {
+    let factors: Vec<String> = DIMENSION_NAMES.iter().zip(dimension)
+        .filter(|(_, exp)| **exp != 0)
+        .map(|(name, exp)| if *exp == 1 { name.to_string() } else { format!("{}^{}", name, exp) })
+        .collect();
+    if factors.is_empty() { "1".to_string() } else { factors.join("·") }
+}
+
+/// Parses values like "1.5 m" or "30 km/h" into the value in SI base units and the exponents of kg, m, s, A, K, mol and cd,
+/// without knowing the type of the value. Used to check values given in attributes at compile time.
+pub fn parse_in_base_units(value: &str) -> Result<(f64, [i32; 7]), UnitParseError> {
+    let value = value.trim();
+    let number_end = value
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+        .unwrap_or(value.len());
+    // an exponent marker directly followed by a letter is part of the unit
+    let number_end = value[..number_end].trim_end_matches(['e', 'E']).len();
+    let (number, unit) = value.split_at(number_end);
+    let number: f64 = number.parse().map_err(|_| UnitParseError::InvalidNumber(number.to_string()))?;
+    let (scale, dimension) = parse_unit(unit.trim())?;
+    Ok((number * scale, dimension))
+}
+
+/// Parses values like "1.5 m", "150 cm", "30 km/h" or "9.81 m/s^2". 
+/// The unit has to match the dimension of the value, dimensionless values can be given without unit.
+impl<L, M, T, A, K, Mol, Cd> FromStr for SiValue<L, M, T, A, K, Mol, Cd>
+where
+    L: Integer,
+    M: Integer,
+    T: Integer,
+    A: Integer,
+    K: Integer,
+    Mol: Integer,
+    Cd: Integer,
+{
+    type Err = UnitParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (value, dimension) = parse_in_base_units(value)?;
+        let expected = [M::to_i32(), L::to_i32(), T::to_i32(), A::to_i32(), K::to_i32(), Mol::to_i32(), Cd::to_i32()];
+        if dimension != expected {
+            return Err(UnitParseError::DimensionMismatch {
+                expected: format_dimension(&expected),
+                found: format_dimension(&dimension),
+            });
+        }
+        Ok(Self::from_value_in_base_units(value))
+    }
+}
+
+impl<L, M, T, A, K, Mol, Cd> SiValue<L, M, T, A, K, Mol, Cd>
+where
+    L: Integer,
+    M: Integer,
+    T: Integer,
+    A: Integer,
+    K: Integer,
+    Mol: Integer,
+    Cd: Integer,
+{
+    /// Formats the value in SI base units so that it can be parsed again, e.g. "1.5 m" or "9.81 m·s^-2".
+    pub fn format_in_base_units(&self) -> String {
+        let dimension = [M::to_i32(), L::to_i32(), T::to_i32(), A::to_i32(), K::to_i32(), Mol::to_i32(), Cd::to_i32()];
+        if dimension == [0; 7] {
+            return self.as_value_in_base_units().to_string();
+        }
+        format!("{} {}", self.as_value_in_base_units(), format_dimension(&dimension))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::{Distance, Velocity, Acceleration, Scalar, Radian, Mass};
+
+    #[test]
+    fn test_parse_si_values() {
+        assert_eq!("1.5 m".parse::<Distance>(), Ok(Distance::meters(1.5)));
+        assert_eq!("150 cm".parse::<Distance>(), Ok(Distance::meters(1.5)));
+        assert_eq!("2km".parse::<Distance>(), Ok(Distance::meters(2000.0)));
+        assert_eq!("250 g".parse::<Mass>(), Ok(Mass::kilograms(0.25)));
+        assert!(("36 km/h".parse::<Velocity>().unwrap().as_meters_per_second() - 10.0).abs() < 1e-12);
+        assert_eq!("9.81 m/s^2".parse::<Acceleration>(), "9.81 m/s²".parse::<Acceleration>());
+        assert_eq!("0.5".parse::<Scalar>(), Ok(Scalar::scalar(0.5)));
+        assert!(("180 deg".parse::<Radian>().unwrap().as_value_in_base_units() - std::f64::consts::PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_format_in_base_units() {
+        assert_eq!(Distance::meters(1.5).format_in_base_units(), "1.5 m");
+        assert_eq!(Scalar::scalar(0.5).format_in_base_units(), "0.5");
+        let acceleration = "9.81 m/s^2".parse::<Acceleration>().unwrap();
+        assert_eq!(acceleration.format_in_base_units().parse::<Acceleration>(), Ok(acceleration));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "2 s".parse::<Distance>(), 
+            Err(UnitParseError::DimensionMismatch { expected: "m".to_string(), found: "s".to_string() })
+        );
+        assert_eq!("1.5".parse::<Distance>(), Err(UnitParseError::DimensionMismatch { expected: "m".to_string(), found: "1".to_string() }));
+        assert_eq!("1.5 parsec".parse::<Distance>(), Err(UnitParseError::UnknownUnit("parsec".to_string())));
+        assert_eq!("fast".parse::<Velocity>(), Err(UnitParseError::InvalidNumber("".to_string())));
+    }
+}
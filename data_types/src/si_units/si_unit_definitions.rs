@@ -392,6 +392,10 @@ impl<L, M, T, A, K, Mol, Cd> SiValue<L, M, T, A, K, Mol, Cd> {
     pub fn as_value_in_base_units(&self) -> f64 {
         self.value
     }
+
+    pub fn from_value_in_base_units(value: f64) -> Self {
+        Self::new(value)
+    }
 }
 
 impl<A: Eq, B: Eq, C: Eq, D: Eq, E: Eq, F: Eq, G: Eq> Eq for SiValue<A, B, C, D, E, F, G> {}
@@ -421,6 +425,7 @@ where
     Mol: Integer + Neg,
     Cd: Integer + Neg,
 {
+    #[allow(clippy::type_complexity)]
     pub fn inverse(&self) -> SiValue<Negate<L>, Negate<M>, Negate<T>, Negate<A>, Negate<K>, Negate<Mol>, Negate<Cd>> {
         SiValue::new(1.0 / self.value)
     }
@@ -436,6 +441,7 @@ where
     Mol: Integer + std::ops::Div<typenum::P2> + Rem<P2, Output = Z0>,
     Cd: Integer + std::ops::Div<typenum::P2> + Rem<P2, Output = Z0>,
 {
+    #[allow(clippy::type_complexity)]
     pub fn sqrt(&self) -> SiValue<
         typenum::Quot<L, typenum::P2>,
         typenum::Quot<M, typenum::P2>,
@@ -942,7 +948,7 @@ where
 
 fn format_unit(name: &str, exp: i32) -> String {
     match exp {
-        1 => format!("{}", name),
+        1 => name.to_string(),
         _ => format!("{}{}", name, to_superscript(exp)),
     }
 }
@@ -972,6 +978,7 @@ fn to_superscript(n: i32) -> String {
 }
 
 #[cfg(test)]
+#[allow(clippy::op_ref)]
 mod tests {
     use super::*;
 
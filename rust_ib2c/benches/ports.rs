@@ -0,0 +1,154 @@
+//! Compares the port implementation against the previous `RwLock` based implementation,
+//! which followed passthrough chains on every access.
+//!
+//! Run with `cargo bench -p rust_ib2c`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rust_ib2c::prelude::*;
+
+/// Previous port implementation, kept as a baseline.
+mod rwlock_port {
+    use std::sync::{Arc, RwLock};
+
+    enum PortMode<T> {
+        Buffer(Option<Arc<T>>),
+        Passthrough(Port<T>),
+    }
+
+    pub struct Port<T> {
+        mode: Arc<RwLock<PortMode<T>>>,
+    }
+
+    impl<T> Default for Port<T> {
+        fn default() -> Self {
+            Self {
+                mode: Arc::new(RwLock::new(PortMode::Buffer(None))),
+            }
+        }
+    }
+
+    impl<T> Port<T> {
+        pub fn send(&self, data: T) {
+            if let PortMode::Passthrough(source_port) = &*self.mode.read().unwrap() {
+                source_port.send(data);
+                return;
+            }
+            *self.mode.write().unwrap() = PortMode::Buffer(Some(Arc::new(data)));
+        }
+
+        pub fn get_reference(&self) -> Option<Arc<T>> {
+            match &*self.mode.read().unwrap() {
+                PortMode::Buffer(buffer) => buffer.clone(),
+                PortMode::Passthrough(source_port) => source_port.get_reference(),
+            }
+        }
+
+        pub fn connect_to_source(&self, source: &Port<T>) {
+            *self.mode.write().unwrap() = PortMode::Passthrough(Port { mode: Arc::clone(&source.mode) });
+        }
+    }
+
+    /// Receive port buffering the data of its port on update, like the lock-free `ReceivePort`.
+    pub struct ReceivePort<T> {
+        pub inner: Port<T>,
+        buffer: Option<Arc<T>>,
+    }
+
+    impl<T> Default for ReceivePort<T> {
+        fn default() -> Self {
+            Self {
+                inner: Port::default(),
+                buffer: None,
+            }
+        }
+    }
+
+    impl<T> ReceivePort<T> {
+        pub fn update(&mut self) {
+            self.buffer = self.inner.get_reference();
+        }
+
+        pub fn get_reference(&self) -> Option<&T> {
+            self.buffer.as_deref()
+        }
+    }
+}
+
+const FAN_OUT: [usize; 3] = [1, 8, 64];
+const CHAIN_DEPTH: [usize; 3] = [1, 8, 32];
+
+fn fan_out(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fan_out");
+    for count in FAN_OUT {
+        let source: SendPort<u64> = SendPort::default();
+        let mut targets: Vec<ReceivePort<u64>> = port_vec(count);
+        targets.connect_all_to_source(&source).unwrap();
+        group.bench_with_input(BenchmarkId::new("lock_free", count), &count, |b, _| {
+            b.iter(|| {
+                source.send(black_box(42));
+                for target in targets.iter_mut() {
+                    target.update();
+                    black_box(target.get_reference());
+                }
+            })
+        });
+
+        let source = rwlock_port::Port::default();
+        let mut targets: Vec<rwlock_port::ReceivePort<u64>> = (0..count).map(|_| rwlock_port::ReceivePort::default()).collect();
+        targets.iter().for_each(|target| target.inner.connect_to_source(&source));
+        group.bench_with_input(BenchmarkId::new("rwlock", count), &count, |b, _| {
+            b.iter(|| {
+                source.send(black_box(42));
+                for target in targets.iter_mut() {
+                    target.update();
+                    black_box(target.get_reference());
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn deep_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deep_chain");
+    for depth in CHAIN_DEPTH {
+        let source: SendPort<u64> = SendPort::default();
+        let chain: Vec<ReceivePort<u64>> = port_vec(depth);
+        chain[0].connect_to_source(&source).unwrap();
+        for index in 1..depth {
+            chain[index].connect_to_source(&chain[index - 1]).unwrap();
+        }
+        let mut target = chain[depth - 1].clone();
+        group.bench_with_input(BenchmarkId::new("lock_free", depth), &depth, |b, _| {
+            b.iter(|| {
+                source.send(black_box(42));
+                target.update();
+                black_box(target.get_reference());
+            })
+        });
+
+        let source = rwlock_port::Port::default();
+        // the receive port is the last link of the chain
+        let chain: Vec<rwlock_port::Port<u64>> = (1..depth).map(|_| rwlock_port::Port::default()).collect();
+        let mut previous = &source;
+        for port in &chain {
+            port.connect_to_source(previous);
+            previous = port;
+        }
+        let mut target = rwlock_port::ReceivePort::default();
+        target.inner.connect_to_source(previous);
+        group.bench_with_input(BenchmarkId::new("rwlock", depth), &depth, |b, _| {
+            b.iter(|| {
+                source.send(black_box(42));
+                target.update();
+                black_box(target.get_reference());
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, fan_out, deep_chain);
+criterion_main!(benches);
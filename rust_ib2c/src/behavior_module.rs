@@ -1,8 +1,35 @@
-use std::ops::{Deref, DerefMut};
+use std::{ops::{Deref, DerefMut}, panic::{catch_unwind, AssertUnwindSafe}, time::{Duration, Instant}};
 
 use rust_ib2c_shared_data::SharedData;
 
-use crate::{prelude::*, tcp_server::Parent};
+use crate::{network::NodeKind, port::Port, prelude::*, tcp_server::Parent};
+
+/// Determines when [`Module::control`] is called by a [`BehaviorModule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ControlMode {
+    /// Call [`Module::control`] every cycle before the target rating is read.
+    #[default]
+    Always,
+    /// Call [`Module::control`] only when the activity of the module is greater than zero.
+    WhenActive,
+}
+
+/// Determines when a [`BehaviorModule`] is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionMode {
+    /// Execute the module every cycle time.
+    #[default]
+    Periodic,
+    /// Execute the module when one of its receive ports receives new data, at most once per cycle time.
+    /// All receive ports and parameters are watched unless ports are selected with [`BehaviorModule::trigger_on`].
+    /// If `heartbeat` is set, the module is also executed when no data was received for this period.
+    OnChange {
+        heartbeat: Option<Duration>,
+    },
+}
+
+/// Longest time a module waiting for new data sleeps before checking if the system is shutting down.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
 /// Behavior module wrapper to run modules in their own threads.
 pub struct BehaviorModule<M> 
@@ -15,6 +42,19 @@ where
     last_update: std::time::Instant,
     parent: Parent,
     loop_count: u64,
+    activation_dynamics: ActivationDynamics,
+    last_activity: MetaSignal,
+    activation_threshold: MetaSignal,
+    active: bool,
+    control_mode: ControlMode,
+    fault_policy: FaultPolicy,
+    fault: Option<String>,
+    frozen: bool,
+    generation: u64,
+    execution_mode: ExecutionMode,
+    trigger: ChangeTrigger,
+    /// Parameters [`Module::on_parameter_changed`] was not called for yet.
+    pending_parameter_changes: Vec<&'static str>,
 }
 
 impl<M> DerefMut for BehaviorModule<M> 
@@ -42,34 +82,229 @@ where
     M: Module + Send + 'static
 {
     /// Creates a new behavior module with the given name and cycle time.
+    /// Parameters are loaded from the parameter file of the system, if one was given to [`SpawnMainGroup!`][crate::SpawnMainGroup],
+    /// and can be saved again with [`BehaviorGroup::save_parameters`].
     pub fn with_name(name: &str, cycle_time: std::time::Duration, parent: &Parent) -> Self {
+        let parent = parent.child(name);
+        let mut module = M::init();
+        if let Err(error) = module.declare_parameters() {
+            panic!("Invalid parameter declaration in module '{}': {}", parent.path, error);
+        }
+        if let Some(parameters) = &parent.parameters {
+            parameters.load(&parent.path, &module);
+        }
+        parent.live_parameters.register(&parent.path, &module);
         Self {
             name: name.to_string(),
-            module: M::init(),
+            module,
             cycle_time,
             last_update: std::time::Instant::now(),
-            parent: Parent {
-                path: format!("{}/{}", parent.path, name),
-                tcp_server: parent.tcp_server.clone(),
-            },
             loop_count: 0,
+            activation_dynamics: ActivationDynamics::default(),
+            last_activity: MetaSignal::LOW,
+            activation_threshold: MetaSignal::LOW,
+            active: false,
+            control_mode: ControlMode::default(),
+            fault_policy: FaultPolicy::default(),
+            fault: None,
+            frozen: false,
+            generation: parent.supervisor.as_ref().map_or(0, |supervisor| supervisor.generation()),
+            parent,
+            execution_mode: ExecutionMode::default(),
+            trigger: ChangeTrigger::new(),
+            pending_parameter_changes: Vec::new(),
+        }
+    }
+
+    pub fn cycle_time(&self) -> std::time::Duration {
+        self.cycle_time
+    }
+
+    /// Sets how the module reacts to errors returned by [`Module::try_transfere`] and to panics.
+    /// Defaults to [`FaultPolicy::Freeze`].
+    /// Ignored if the module is spawned in a group with a [`RestartStrategy`].
+    pub fn set_fault_policy(&mut self, fault_policy: FaultPolicy) {
+        self.fault_policy = fault_policy;
+    }
+
+    /// Runs a function on the module, catching returned errors and panics.
+    /// Returns `None` if the module faulted.
+    fn run_guarded<R>(&mut self, function: impl FnOnce(&mut M) -> Result<R, ModuleError>) -> Option<R> {
+        let error = match catch_unwind(AssertUnwindSafe(|| function(&mut self.module))) {
+            Ok(Ok(result)) => return Some(result),
+            Ok(Err(error)) => error,
+            Err(payload) => ModuleError::from_panic(payload),
+        };
+        self.handle_fault(error);
+        None
+    }
+
+    fn handle_fault(&mut self, error: ModuleError) {
+        eprintln!("Module '{}' faulted: {}", self.parent.path, error);
+        self.fault = Some(error.to_string());
+        if let Some(supervisor) = self.parent.supervisor.clone() {
+            if supervisor.register_restart() {
+                self.generation = supervisor.generation();
+                self.restart();
+            } else {
+                eprintln!("Restart intensity of '{}' and its enclosing groups exceeded, shutting down", self.parent.path);
+                self.frozen = true;
+                self.parent.request_stop();
+            }
+            return;
+        }
+        match self.fault_policy {
+            FaultPolicy::Freeze => {
+                self.frozen = true;
+            }
+            FaultPolicy::Restart => {
+                self.restart();
+            }
+            FaultPolicy::Shutdown => {
+                self.frozen = true;
+                self.parent.request_stop();
+            }
+        }
+    }
+
+    /// Stops the module, re-creates it using [`Module::init`] and moves the connected ports to the new instance.
+    fn restart(&mut self) {
+        if catch_unwind(AssertUnwindSafe(|| self.module.on_stop())).is_err() {
+            eprintln!("Module '{}' panicked while stopping for a restart", self.parent.path);
+        }
+        let mut module = M::init();
+        module.swap_ports(&mut self.module);
+        self.module = module;
+        self.active = false;
+        if catch_unwind(AssertUnwindSafe(|| self.module.on_start())).is_err() {
+            eprintln!("Module '{}' failed to restart", self.parent.path);
+            self.frozen = true;
+        }
+        self.pending_parameter_changes = self.module.parameter_names();
+    }
+
+    /// Calls [`Module::on_parameter_changed`] for the parameters changed in the last update and for the pending ones.
+    /// Changes are kept while the module is frozen.
+    fn deliver_parameter_changes(&mut self) {
+        for name in self.module.changed_parameters() {
+            if !self.pending_parameter_changes.contains(&name) {
+                self.pending_parameter_changes.push(name);
+            }
+        }
+        if self.frozen {
+            return;
+        }
+        for name in std::mem::take(&mut self.pending_parameter_changes) {
+            if self.frozen {
+                break;
+            }
+            self.run_guarded(|module| {
+                module.on_parameter_changed(name);
+                Ok(())
+            });
+        }
+    }
+
+    /// Restarts the module if another module of an [`RestartStrategy::AllForOne`] group faulted.
+    fn follow_supervisor(&mut self) {
+        let Some(generation) = self.parent.supervisor.as_ref().map(|supervisor| supervisor.generation()) else {
+            return;
+        };
+        if generation != self.generation {
+            self.generation = generation;
+            if !self.frozen {
+                self.restart();
+            }
+        }
+    }
+
+    /// Sets whether [`Module::control`] is called every cycle or only while the module is active.
+    /// Defaults to [`ControlMode::Always`].
+    pub fn set_control_mode(&mut self, control_mode: ControlMode) {
+        self.control_mode = control_mode;
+    }
+
+    /// Sets whether the module is executed every cycle or when its inputs change.
+    /// Defaults to [`ExecutionMode::Periodic`].
+    pub fn set_execution_mode(&mut self, execution_mode: ExecutionMode) {
+        self.execution_mode = execution_mode;
+    }
+
+    /// Executes the module when data is sent to the port in [`ExecutionMode::OnChange`].
+    /// Once a port is selected, the other receive ports of the module no longer trigger an execution.
+    pub fn trigger_on<T: PortSerialization>(&self, port: &Port<T>) {
+        self.trigger.watch(port);
+    }
+
+    /// Waits until a watched port receives data, the heartbeat expires or the system is shutting down.
+    fn wait_for_change(&self, heartbeat: Option<Duration>, cycle_start: Instant) {
+        let deadline = heartbeat.map(|heartbeat| cycle_start + heartbeat);
+        while !self.parent.stop_requested() {
+            let timeout = match deadline {
+                Some(deadline) => deadline.saturating_duration_since(Instant::now()).min(STOP_POLL_INTERVAL),
+                None => STOP_POLL_INTERVAL,
+            };
+            if self.trigger.wait_timeout(timeout) || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return;
+            }
         }
     }
 
+    /// Sets the dynamics used to ramp or smooth the activity of the module.
+    /// Defaults to [`ActivationDynamics::Instant`].
+    pub fn set_activation_dynamics(&mut self, activation_dynamics: ActivationDynamics) {
+        self.activation_dynamics = activation_dynamics;
+    }
+
+    /// Sets the threshold the activity has to exceed to trigger [`Module::on_activate`].
+    /// [`Module::on_deactivate`] is triggered once the activity falls back to or below the threshold.
+    /// Defaults to [`MetaSignal::LOW`].
+    pub fn set_activation_threshold(&mut self, activation_threshold: MetaSignal) {
+        self.activation_threshold = activation_threshold;
+    }
+
     /// Spawns the behavior module in its own thread. 
-    /// Use the [`spawn`] attribute macro to automatically call spawn on the end of the [`init`][Group::init] function of a [`Group`].
+    /// Use the [`spawn`] attribute macro to automatically call spawn on the end of the [`init`][Group::init] function of a [`Group`],
+    /// or register modules created in loops or helper functions with [`Parent::spawn`].
     pub fn spawn(mut self) 
     {
         println!("Spawned module: {}", self.name);
+        self.parent.network.register(&self.parent.path, NodeKind::Module, self.module.port_links());
         let _ = std::thread::spawn(move || {
-            loop {
-                let start = std::time::Instant::now();
+            self.parent.start_gate.wait();
+            if self.parent.stop_requested() {
+                return;
+            }
+            if let ExecutionMode::OnChange { .. } = self.execution_mode 
+                && self.trigger.watched_ports() == 0 
+            {
+                self.module.watch_receive_ports(&self.trigger);
+            }
+            self.run_guarded(|module| {
+                module.on_start();
+                Ok(())
+            });
+            self.pending_parameter_changes = self.module.parameter_names();
+            while !self.parent.stop_requested() {
+                self.follow_supervisor();
+                let start = Instant::now();
                 let delta_time = start.duration_since(self.last_update);
                 self.last_update = start;
                 self.set_delta_time(delta_time);
                 self.update_all_ports();
-                self.transfere();
-                let target_rating = self.module.target_rating();
+                self.deliver_parameter_changes();
+                let control_mode = self.control_mode;
+                let target_rating = if self.frozen {
+                    MetaSignal::LOW
+                } else {
+                    self.run_guarded(|module| {
+                        module.sense();
+                        if control_mode == ControlMode::Always {
+                            module.try_transfere()?;
+                        }
+                        Ok(module.target_rating())
+                    }).unwrap_or(MetaSignal::LOW)
+                };
 
                 let stimulation = self.module.get_stimulation().unwrap_or(MetaSignal::HIGH);
                 let inhibition = self.get_inhibition().unwrap_or(MetaSignal::LOW);
@@ -77,10 +312,34 @@ where
                     stimulation, 
                     MetaSignal::HIGH - inhibition
                 );
-                let activity = MetaSignal::min(
-                    potential,
-                    target_rating,
-                );
+                let activity = if self.frozen {
+                    MetaSignal::LOW
+                } else {
+                    self.activation_dynamics.apply(
+                        self.last_activity,
+                        MetaSignal::min(potential, target_rating),
+                        delta_time,
+                    )
+                };
+                self.last_activity = activity;
+
+                if !self.frozen && control_mode == ControlMode::WhenActive && activity > MetaSignal::LOW {
+                    self.run_guarded(|module| module.try_transfere());
+                }
+
+                let active = activity > self.activation_threshold;
+                if !self.frozen && active && !self.active {
+                    self.run_guarded(|module| {
+                        module.on_activate();
+                        Ok(())
+                    });
+                } else if !self.frozen && !active && self.active {
+                    self.run_guarded(|module| {
+                        module.on_deactivate();
+                        Ok(())
+                    });
+                }
+                self.active = active;
 
                 self.set_activity(activity);
                 self.set_target_rating(target_rating);
@@ -97,7 +356,9 @@ where
                     target_rating: *target_rating,
                     stimulation: *stimulation,
                     inhibition: *inhibition,
-                    data: port_data.into_iter().map(|(name, data)| (name.to_string(), data)).collect(),
+                    data: port_data,
+                    fault: self.fault.clone(),
+                    parameters: self.module.parameter_info(),
                 };
                 self.parent.tcp_server.send(shared_data);
                 
@@ -114,8 +375,348 @@ where
                 if elapsed < self.cycle_time {
                     spin_sleep::sleep(self.cycle_time - elapsed);
                 } 
+                if let ExecutionMode::OnChange { heartbeat } = self.execution_mode {
+                    self.wait_for_change(heartbeat, start);
+                }
+            }
+            if !self.frozen {
+                self.run_guarded(|module| {
+                    module.on_stop();
+                    Ok(())
+                });
             }
         });
 
     }
-}
\ No newline at end of file
+}
+
+impl<M> Spawnable for BehaviorModule<M>
+where
+    M: Module + Send + 'static
+{
+    fn spawn(self) {
+        BehaviorModule::spawn(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::{atomic::{AtomicUsize, Ordering}, Arc}, time::Duration};
+
+    use crate::prelude::*;
+    use crate::tcp_server::Parent;
+    use crate::wait_until;
+
+    #[module]
+    struct LifecycleCounter {
+        cycles: Arc<AtomicUsize>,
+        starts: Arc<AtomicUsize>,
+        activations: Arc<AtomicUsize>,
+        deactivations: Arc<AtomicUsize>,
+        stops: Arc<AtomicUsize>,
+    }
+
+    impl Module for LifecycleCounter {
+        fn transfere(&mut self) {
+            self.cycles.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+
+        fn on_start(&mut self) {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_activate(&mut self) {
+            self.activations.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_deactivate(&mut self) {
+            self.deactivations.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_stop(&mut self) {
+            self.stops.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[module]
+    struct SenseControlCounter {
+        senses: Arc<AtomicUsize>,
+        controls: Arc<AtomicUsize>,
+    }
+
+    impl Module for SenseControlCounter {
+        fn sense(&mut self) {
+            self.senses.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn transfere(&mut self) {
+            self.controls.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[test]
+    fn test_control_when_active() {
+        let parent = Parent::default();
+        let stimulation = SendPort::default();
+        stimulation.send(MetaSignal::LOW);
+
+        let mut module = BehaviorModule::<SenseControlCounter>::with_name("SenseControlCounter", Duration::from_millis(1), &parent);
+        module.set_control_mode(ControlMode::WhenActive);
+        module.stimulation.connect_to_source(&stimulation).unwrap();
+        let senses = module.senses.clone();
+        let controls = module.controls.clone();
+        module.spawn();
+
+        // sense runs every cycle, so several inactive cycles have passed once it was called a few times
+        assert!(wait_until(|| senses.load(Ordering::SeqCst) >= 5));
+        assert_eq!(controls.load(Ordering::SeqCst), 0);
+
+        stimulation.send(MetaSignal::HIGH);
+        assert!(wait_until(|| controls.load(Ordering::SeqCst) > 0));
+        parent.request_stop();
+    }
+
+    #[module]
+    struct ParameterCounter {
+        par_gain: ParameterPort<f64>,
+        executions: Arc<AtomicUsize>,
+    }
+
+    impl Module for ParameterCounter {
+        fn sense(&mut self) {
+            self.executions.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn transfere(&mut self) {
+            // calibrates the gain in the first execution, the change executes the module once more
+            if self.executions.load(Ordering::SeqCst) == 1 {
+                self.par_gain.set(2.0).unwrap();
+            }
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[test]
+    fn test_execution_on_change() {
+        let parent = Parent::default();
+        let stimulation = SendPort::default();
+        let inhibition = SendPort::default();
+
+        let mut on_change = BehaviorModule::<SenseControlCounter>::with_name("OnChange", Duration::from_millis(1), &parent);
+        on_change.set_execution_mode(ExecutionMode::OnChange { heartbeat: None });
+        on_change.stimulation.connect_to_source(&stimulation).unwrap();
+        on_change.inhibition.connect_to_source(&inhibition).unwrap();
+        on_change.trigger_on(&on_change.stimulation);
+        // only the selected port notifies the trigger
+        inhibition.send(MetaSignal::LOW);
+        assert!(!on_change.trigger.wait_timeout(Duration::ZERO));
+        stimulation.send(MetaSignal::HIGH);
+        assert!(on_change.trigger.wait_timeout(Duration::ZERO));
+        let changes = on_change.senses.clone();
+        on_change.spawn();
+
+        // executed once on start and once per send
+        assert!(wait_until(|| changes.load(Ordering::SeqCst) == 1));
+        for expected in 2..=4 {
+            stimulation.send(MetaSignal::HIGH);
+            assert!(wait_until(|| changes.load(Ordering::SeqCst) == expected));
+        }
+
+        let mut heartbeat = BehaviorModule::<SenseControlCounter>::with_name("Heartbeat", Duration::from_millis(1), &parent);
+        heartbeat.set_execution_mode(ExecutionMode::OnChange { heartbeat: Some(Duration::from_millis(10)) });
+        let heartbeats = heartbeat.senses.clone();
+        heartbeat.spawn();
+        assert!(wait_until(|| heartbeats.load(Ordering::SeqCst) >= 3));
+        parent.request_stop();
+    }
+
+    #[test]
+    fn test_execution_on_parameter_change() {
+        let parent = Parent::default();
+        let mut module = BehaviorModule::<ParameterCounter>::with_name("ParameterCounter", Duration::from_millis(1), &parent);
+        module.set_execution_mode(ExecutionMode::OnChange { heartbeat: None });
+        let executions = module.executions.clone();
+        module.spawn();
+
+        assert!(wait_until(|| executions.load(Ordering::SeqCst) == 2));
+        parent.request_stop();
+    }
+
+    #[module]
+    struct FaultyModule {
+        pub out_cycles: SendPort<u32>,
+        starts: Arc<AtomicUsize>,
+        stops: Arc<AtomicUsize>,
+        cycles: u32,
+    }
+
+    impl Module for FaultyModule {
+        // the cycles are computed in try_transfere to report errors
+        fn transfere(&mut self) {}
+
+        fn try_transfere(&mut self) -> Result<(), ModuleError> {
+            self.cycles += 1;
+            self.out_cycles.send(self.cycles);
+            match self.cycles {
+                3 => Err(ModuleError::new("third cycle")),
+                5 => panic!("fifth cycle"),
+                _ => Ok(()),
+            }
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+
+        fn on_start(&mut self) {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_stop(&mut self) {
+            self.stops.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_port_directions() {
+        let module = FaultyModule::init();
+        module.out_cycles.send(1);
+        let port_data = module.all_port_data();
+        assert_eq!(port_data.len(), 1);
+        assert_eq!(port_data[0].0, "out_cycles");
+        assert_eq!(port_data[0].1, PortDirection::Output);
+    }
+
+    type Counter = SendPort<u32>;
+
+    #[module]
+    struct PortCollections {
+        #[port]
+        out_count: Counter,
+        in_optional: Option<ReceivePort<u32>>,
+        in_values: Vec<ReceivePort<u32>>,
+    }
+
+    impl Module for PortCollections {
+        fn transfere(&mut self) {}
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[test]
+    fn test_port_collections() {
+        let source = SendPort::default();
+        let mut module = PortCollections {
+            in_optional: Some(ReceivePort::default()),
+            in_values: vec![ReceivePort::default(), ReceivePort::default()],
+            ..Default::default()
+        };
+        for port in module.in_values.iter().chain(&module.in_optional) {
+            port.connect_to_source(&source).unwrap();
+        }
+        source.send(7);
+        module.out_count.send(1);
+        module.update_all_ports();
+
+        assert_eq!(module.in_optional.as_ref().and_then(ReceivePort::get), Some(7));
+        assert_eq!(module.in_values[1].get(), Some(7));
+        let names: Vec<_> = module.all_port_data().into_iter().map(|(name, _, _)| name).collect();
+        assert_eq!(names, vec!["out_count", "in_optional", "in_values[0]", "in_values[1]"]);
+    }
+
+    #[test]
+    fn test_fault_policy_freeze() {
+        let parent = Parent::default();
+        let module = BehaviorModule::<FaultyModule>::with_name("FaultyModule", Duration::from_millis(1), &parent);
+        let out_cycles = OutputPort::from(&module.out_cycles);
+        let activity = OutputPort::from(module.get_activity_port());
+        module.spawn();
+
+        // the module stops computing in the faulting cycle and its activity drops to LOW
+        assert!(wait_until(|| out_cycles.get() == Some(3) && activity.get() == Some(MetaSignal::LOW)));
+        parent.request_stop();
+    }
+
+    #[test]
+    fn test_fault_policy_restart() {
+        let parent = Parent::default();
+        let mut module = BehaviorModule::<FaultyModule>::with_name("FaultyModule", Duration::from_millis(1), &parent);
+        module.set_fault_policy(FaultPolicy::Restart);
+        let mut receive_port = ReceivePort::default();
+        receive_port.connect_to_source(&module.out_cycles).unwrap();
+        let starts = module.starts.clone();
+        let stops = module.stops.clone();
+        module.spawn();
+
+        // restarted instances are created with Module::init and count their cycles from the start,
+        // so a cycle below the fault cycle after the fault is only sent by a new instance
+        let mut faulted = false;
+        assert!(wait_until(|| {
+            receive_port.update();
+            match receive_port.get() {
+                Some(3) => faulted = true,
+                Some(cycles) => return faulted && cycles < 3,
+                None => {}
+            }
+            false
+        }));
+        // the new instances do not share the counters of the first instance, which is stopped before it is replaced
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+        assert_eq!(stops.load(Ordering::SeqCst), 1);
+        parent.request_stop();
+    }
+
+    #[test]
+    fn test_lifecycle_hooks() {
+        let parent = Parent::default();
+        let stimulation = SendPort::default();
+        stimulation.send(MetaSignal::LOW);
+
+        let module = BehaviorModule::<LifecycleCounter>::with_name("LifecycleCounter", Duration::from_millis(1), &parent);
+        module.stimulation.connect_to_source(&stimulation).unwrap();
+        let cycles = module.cycles.clone();
+        let starts = module.starts.clone();
+        let activations = module.activations.clone();
+        let deactivations = module.deactivations.clone();
+        let stops = module.stops.clone();
+        module.spawn();
+
+        // let some cycles pass to see that an inactive module is not activated
+        let wait_cycles = |count: usize| {
+            let target = cycles.load(Ordering::SeqCst) + count;
+            assert!(wait_until(|| cycles.load(Ordering::SeqCst) >= target));
+        };
+        wait_cycles(5);
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+        assert_eq!(activations.load(Ordering::SeqCst), 0);
+
+        stimulation.send(MetaSignal::HIGH);
+        assert!(wait_until(|| activations.load(Ordering::SeqCst) == 1));
+        wait_cycles(5);
+        assert_eq!(activations.load(Ordering::SeqCst), 1);
+        assert_eq!(deactivations.load(Ordering::SeqCst), 0);
+
+        stimulation.send(MetaSignal::LOW);
+        assert!(wait_until(|| deactivations.load(Ordering::SeqCst) == 1));
+        wait_cycles(5);
+        assert_eq!(activations.load(Ordering::SeqCst), 1);
+        assert_eq!(deactivations.load(Ordering::SeqCst), 1);
+
+        parent.request_stop();
+        assert!(wait_until(|| stops.load(Ordering::SeqCst) == 1));
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,152 @@
+use std::{any::Any, collections::VecDeque, fmt::Display, sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex}, time::{Duration, Instant}};
+
+/// Error returned by [`Module::try_transfere`][crate::traits::Module::try_transfere].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleError {
+    message: String,
+}
+
+impl ModuleError {
+    /// Creates a new error with the given message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Creates an error from the payload of a caught panic.
+    pub(crate) fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        Self::new(format!("panicked: {}", message))
+    }
+}
+
+impl Display for ModuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ModuleError {}
+
+impl From<&str> for ModuleError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<String> for ModuleError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+/// Determines how a [`BehaviorModule`][crate::behavior_module::BehaviorModule] reacts
+/// when its module returns a [`ModuleError`] or panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FaultPolicy {
+    /// Stop executing the module. Outputs keep their last values and activity and target rating are set to LOW.
+    #[default]
+    Freeze,
+    /// Re-create the module using [`Module::init`][crate::traits::Module::init] and keep its port connections.
+    Restart,
+    /// Shut down the whole system.
+    Shutdown,
+}
+
+/// Restart strategy of a [`Group`][crate::traits::Group] for the modules spawned in it.
+/// If more than `max_restarts` restarts happen within `period`, the fault is escalated to the supervisor of the
+/// enclosing group, which restarts all modules of this group. If there is no supervised enclosing group,
+/// the whole system is shut down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartStrategy {
+    /// Modules handle faults according to their own [`FaultPolicy`].
+    #[default]
+    None,
+    /// Only the faulted module is restarted.
+    OneForOne {
+        max_restarts: u32,
+        period: Duration,
+    },
+    /// All modules of the group are restarted when one of them faults.
+    AllForOne {
+        max_restarts: u32,
+        period: Duration,
+    },
+}
+
+/// Keeps track of restarts of the modules in a supervised group.
+pub(crate) struct Supervisor {
+    strategy: RestartStrategy,
+    restarts: Mutex<VecDeque<Instant>>,
+    generation: AtomicU64,
+    parent: Option<Arc<Supervisor>>,
+}
+
+impl Supervisor {
+    /// Creates a supervisor for the given strategy, or `None` if the group is not supervised.
+    /// `parent` is the supervisor of the enclosing group, faults are escalated to it.
+    pub(crate) fn new(strategy: RestartStrategy, parent: Option<Arc<Supervisor>>) -> Option<Self> {
+        match strategy {
+            RestartStrategy::None => None,
+            _ => Some(Self {
+                strategy,
+                restarts: Mutex::new(VecDeque::new()),
+                generation: AtomicU64::new(0),
+                parent,
+            }),
+        }
+    }
+
+    /// Registers a restart of a faulted module.
+    /// If the maximum restart intensity is exceeded, the restart of the whole group is registered at the parent.
+    /// Returns false if the fault could not be handled by any supervisor.
+    pub(crate) fn register_restart(&self) -> bool {
+        if self.register_local_restart() {
+            return true;
+        }
+        match &self.parent {
+            Some(parent) if parent.register_restart() => {
+                self.restarts.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+                self.generation.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn register_local_restart(&self) -> bool {
+        let (max_restarts, period) = match self.strategy {
+            RestartStrategy::None => return false,
+            RestartStrategy::OneForOne { max_restarts, period } => (max_restarts, period),
+            RestartStrategy::AllForOne { max_restarts, period } => (max_restarts, period),
+        };
+        let now = Instant::now();
+        let mut restarts = self.restarts.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        while restarts.front().is_some_and(|restart| now.duration_since(*restart) > period) {
+            restarts.pop_front();
+        }
+        if restarts.len() >= max_restarts as usize {
+            return false;
+        }
+        restarts.push_back(now);
+        if let RestartStrategy::AllForOne { .. } = self.strategy {
+            self.generation.fetch_add(1, Ordering::SeqCst);
+        }
+        true
+    }
+
+    /// Generation of the group. Increases every time all modules of the group have to be restarted,
+    /// including restarts of enclosing groups.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst) + self.parent.as_ref().map_or(0, |parent| parent.generation())
+    }
+}
@@ -1,6 +1,30 @@
 use rust_ib2c_shared_data::SharedData;
 
-use crate::{prelude::*, tcp_server::Parent, traits::PortSerialization};
+use crate::{network::NodeKind, prelude::*, tcp_server::Parent, traits::PortSerialization};
+
+// The inputs of a fusion module are named after its fields in the network,
+// the same names are used for the port data sent to the monitor.
+/// Name of the activity inputs of a [`MaximumFusion`].
+pub(crate) const ACTIVITY_INPUTS: &str = "activities";
+/// Name of the target rating inputs of a [`MaximumFusion`].
+pub(crate) const TARGET_RATING_INPUTS: &str = "target_ratings";
+/// Name of the data inputs of a [`MaximumFusion`].
+pub(crate) const DATA_INPUTS: &str = "data_ports";
+
+/// Error returned by [`MaximumFusion::check_input_cycle_time`] if a fusion module has a longer cycle time than one of its inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleTimeError {
+    pub cycle_time: std::time::Duration,
+    pub input_cycle_time: std::time::Duration,
+}
+
+impl std::fmt::Display for CycleTimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cycle time {:?} is longer than the cycle time {:?} of the input", self.cycle_time, self.input_cycle_time)
+    }
+}
+
+impl std::error::Error for CycleTimeError {}
 
 /// Fusion module that selects the output from the module with the highest activity.
 /// If multiple modules have the same activity, the first one encountered is chosen.
@@ -9,7 +33,7 @@ use crate::{prelude::*, tcp_server::Parent, traits::PortSerialization};
 pub struct MaximumFusion<D: Clone + PortSerialization> {
     name: String,
     pub output: SendPort<D>,
-    activitys: Vec<ReceivePort<MetaSignal>>,
+    activities: Vec<ReceivePort<MetaSignal>>,
     target_ratings: Vec<ReceivePort<MetaSignal>>,
     data_ports: Vec<ReceivePort<D>>,
     cycle_time: std::time::Duration,
@@ -27,46 +51,50 @@ where
         Self {
             name: name.to_string(),
             output: SendPort::default(),
-            activitys: Vec::new(),
+            activities: Vec::new(),
             data_ports: Vec::new(),
             cycle_time,
-            parent: Parent {
-                path: format!("{}/{}", parent.path, name),
-                tcp_server: parent.tcp_server.clone(),
-            },
+            parent: parent.child(name),
             loop_count: 0,
             ..Default::default()
         }
     }
 
-    pub fn serialize_port_data(&self) -> Vec<(String, PortData)> {
+    pub fn serialize_port_data(&self) -> Vec<(String, PortDirection, PortData)> {
         let mut port_data = Vec::new();
-        for (index, (data_port, activity_port)) in self.data_ports.iter().zip(self.activitys.iter()).enumerate() {
-            if let Some(data) = data_port.get() && let Some(activity) = activity_port.get() {
-                port_data.push((format!("data_port_{}", index), data.serialize_port_data()));
-                port_data.push((format!("activity_{}", index), PortData::MetaSignal(*activity)));
-            }
-        }
-        if let Some(output) = self.output.get() {
-            port_data.push(("output".to_string(), output.serialize_port_data()));
-        }
-
+        self.data_ports.serialize_port(DATA_INPUTS, &mut port_data);
+        self.activities.serialize_port(ACTIVITY_INPUTS, &mut port_data);
+        self.output.serialize_port("output", &mut port_data);
         port_data
     }
 
     /// Connects a module's output port to the fusion module. Use the [`connect_fusion!`] macro to connect multiple modules at once.
-    pub fn connect_module<M: MetaSignals>(&mut self, module: &M, in_data_port: &SendPort<D>) {
+    pub fn connect_module<M: MetaSignals>(&mut self, module: &M, in_data_port: &SendPort<D>) -> Result<(), ConnectionError> {
         let activity_port = ReceivePort::default();
-        activity_port.connect_to_source(&module.get_activity_port());
-        self.activitys.push(activity_port);
+        activity_port.connect_to_source(module.get_activity_port())?;
 
         let data_port = ReceivePort::default();
-        data_port.connect_to_source(in_data_port);
-        self.data_ports.push(data_port);
+        data_port.connect_to_source(in_data_port)?;
 
         let target_rating_port = ReceivePort::default();
-        target_rating_port.connect_to_source(&module.get_target_rating_port());
+        target_rating_port.connect_to_source(module.get_target_rating_port())?;
+
+        self.activities.push(activity_port);
+        self.data_ports.push(data_port);
         self.target_ratings.push(target_rating_port);
+        Ok(())
+    }
+
+    pub fn cycle_time(&self) -> std::time::Duration {
+        self.cycle_time
+    }
+
+    /// Checks that the fusion module runs at least as fast as an input, so no output of the input is skipped.
+    pub fn check_input_cycle_time(&self, input_cycle_time: std::time::Duration) -> Result<(), CycleTimeError> {
+        if self.cycle_time > input_cycle_time {
+            return Err(CycleTimeError { cycle_time: self.cycle_time, input_cycle_time });
+        }
+        Ok(())
     }
 
     fn max_fusion(&self) -> Option<(MetaSignal, MetaSignal, D)> {
@@ -74,13 +102,13 @@ where
         let mut max_activity = MetaSignal::LOW;
         let mut best_index = 0;
         
-        for (index, activity_port) in self.activitys.iter().enumerate() {
-            if let Some(activity) = activity_port.get() {
-                if activity > max_activity {
-                    max_activity = activity;
-                    best_data = self.data_ports[index].get();
-                    best_index = index;
-                }
+        for (index, activity_port) in self.activities.iter().enumerate() {
+            if let Some(activity) = activity_port.get()
+                && activity > max_activity
+            {
+                max_activity = activity;
+                best_data = self.data_ports[index].get();
+                best_index = index;
             }
         }
 
@@ -96,18 +124,12 @@ where
     pub fn spawn(mut self) 
     {
         println!("Spawned module: {}", self.name);
+        self.parent.network.register(&self.parent.path, NodeKind::Fusion, self.port_links());
         let _ = std::thread::spawn(move || {
-            loop {
+            self.parent.start_gate.wait();
+            while !self.parent.stop_requested() {
                 let start = std::time::Instant::now();
-                for activity_ports in &mut self.activitys {
-                    activity_ports.update();
-                }
-                for data_ports in &mut self.data_ports {
-                    data_ports.update();
-                }
-                for target_rating_ports in &mut self.target_ratings {
-                    target_rating_ports.update();
-                }
+                self.update_all_ports();
                 if let Some((activity, target_rating, output)) = self.max_fusion() {
                     self.set_activity(activity);
                     self.set_target_rating(target_rating);
@@ -126,7 +148,9 @@ where
                     target_rating: *self.target_rating.get().unwrap_or(MetaSignal::LOW),
                     stimulation: *self.get_stimulation().unwrap_or(MetaSignal::HIGH),
                     inhibition: *self.get_inhibition().unwrap_or(MetaSignal::LOW),
-                    data: port_data
+                    data: port_data,
+                    fault: None,
+                    parameters: Vec::new(),
                 };
                 self.parent.tcp_server.send(shared_data);
 
@@ -143,4 +167,14 @@ where
             }
         });
     }
-}
\ No newline at end of file
+}
+
+impl<D> Spawnable for MaximumFusion<D>
+where
+    D: Clone + Default + Send + PortSerialization + 'static,
+    Self: Send + 'static
+{
+    fn spawn(self) {
+        MaximumFusion::spawn(self);
+    }
+}
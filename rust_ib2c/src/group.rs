@@ -1,10 +1,10 @@
-use std::ops::{Deref, DerefMut};
+use std::{ops::{Deref, DerefMut}, path::Path, sync::{atomic::{AtomicBool, Ordering}, Arc}, time::Instant};
 
-use crate::{prelude::*, tcp_server::{Parent, TcpServer}};
+use crate::{fault::Supervisor, network::NodeKind, prelude::*, tcp_server::{Parent, StartGate}};
 
 /// Macro to spawn the main behavior group.
 /// # Example
-/// ```rust
+/// ```rust ignore
 /// use std::time::Duration;
 /// use rust_ib2c::prelude::*;
 /// 
@@ -21,12 +21,81 @@ use crate::{prelude::*, tcp_server::{Parent, TcpServer}};
 /// }
 /// fn main() {
 ///     let main_group = SpawnMainGroup!(MyMainGroup, "MyMainGroup", Duration::from_millis(100));
+///     // or load parameter values of the modules from a file, returns a Result
+///     let main_group = SpawnMainGroup!(MyMainGroup, "MyMainGroup", Duration::from_millis(100), parameters: "robot.toml").unwrap();
 /// }
 #[macro_export]
 macro_rules! SpawnMainGroup {
     ($group_type:ty, $name:expr, $cycle_time:expr) => {
         BehaviorGroup::<$group_type>::main_group($name, $cycle_time)
     };
+    ($group_type:ty, $name:expr, $cycle_time:expr, parameters: $parameter_file:expr) => {
+        BehaviorGroup::<$group_type>::main_group_with_parameters($name, $cycle_time, $parameter_file)
+    };
+}
+
+/// Activity and target rating inputs of the members of a group.
+/// Members are added in [`Group::init`] with the `add_member` method generated by the [`group`] macro.
+#[derive(Default)]
+pub struct GroupMembers {
+    activities: Vec<ReceivePort<MetaSignal>>,
+    target_ratings: Vec<ReceivePort<MetaSignal>>,
+}
+
+impl GroupMembers {
+    /// Connects to the activity and target rating of a module.
+    pub fn add<M: MetaSignals>(&mut self, module: &M) -> Result<(), ConnectionError> {
+        let activity = ReceivePort::default();
+        activity.connect_to_source(module.get_activity_port())?;
+        let target_rating = ReceivePort::default();
+        target_rating.connect_to_source(module.get_target_rating_port())?;
+        self.activities.push(activity);
+        self.target_ratings.push(target_rating);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.activities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.activities.is_empty()
+    }
+}
+
+/// Sends the aggregated activity and target rating of the members to the group, in its own thread like a module.
+struct MemberAggregation {
+    members: GroupMembers,
+    aggregation: ActivityAggregation,
+    activity: SendPort<MetaSignal>,
+    target_rating: SendPort<MetaSignal>,
+    cycle_time: std::time::Duration,
+    stop: Arc<AtomicBool>,
+    start_gate: StartGate,
+}
+
+impl Spawnable for MemberAggregation {
+    fn spawn(mut self) {
+        let _ = std::thread::spawn(move || {
+            self.start_gate.wait();
+            while !self.stop.load(Ordering::Relaxed) {
+                let start = Instant::now();
+                self.members.activities.iter_mut()
+                    .chain(self.members.target_ratings.iter_mut())
+                    .for_each(ReceivePort::update);
+                let members = self.members.activities.iter().zip(&self.members.target_ratings)
+                    .map(|(activity, target_rating)| (activity.get().unwrap_or(MetaSignal::LOW), target_rating.get().unwrap_or(MetaSignal::LOW)));
+                let (activity, target_rating) = self.aggregation.aggregate(members);
+                self.activity.send(activity);
+                self.target_rating.send(target_rating);
+
+                let elapsed = start.elapsed();
+                if elapsed < self.cycle_time {
+                    spin_sleep::sleep(self.cycle_time - elapsed);
+                }
+            }
+        });
+    }
 }
 
 /// Behavior group wrapper to run groups in their own threads.
@@ -35,6 +104,8 @@ where
     M: Group + Default + Send + 'static
 {
     pub module: M,
+    parent: Parent,
+    cycle_time: std::time::Duration,
 }
 
 impl<M> DerefMut for BehaviorGroup<M> 
@@ -65,29 +136,634 @@ where
     pub fn with_name(name: &str, cycle_time: std::time::Duration, parent: &Parent) -> Self {
         println!("Initializing BehaviorGroup: {}", name);
         let mut group = M::default();
-        let parent = Parent {
-            path: format!("{}/{}", parent.path, name),
-            tcp_server: parent.tcp_server.clone(),
-        };
-        group.init(cycle_time, &parent);
+        let mut parent = parent.child(name);
+        if let Some(supervisor) = Supervisor::new(M::RESTART_STRATEGY, parent.supervisor.clone()) {
+            parent.supervisor = Some(Arc::new(supervisor));
+        }
+        Self::initialize(&mut group, cycle_time, &parent);
         Self {
             module: group,
+            parent,
+            cycle_time,
         }
     }
 
     /// Creates a new main behavior group with the given name and cycle time.
     pub fn main_group(name: &str, cycle_time: std::time::Duration) -> Self {
+        let group = Self::create_main_group(name, cycle_time, None);
+        group.start();
+        group
+    }
+
+    /// Creates a new main behavior group and loads the parameters of its modules from a TOML, JSON or YAML file.
+    /// No module is started if the file contains unknown modules, unknown parameters or invalid values.
+    pub fn main_group_with_parameters(name: &str, cycle_time: std::time::Duration, parameter_file: impl AsRef<Path>) -> Result<Self, ParameterError> {
+        Self::load_main_group(name, cycle_time, parameter_file, Self::start)
+    }
+
+    /// Creates a main group with the parameters of the given file and calls `start` if they are valid.
+    fn load_main_group(name: &str, cycle_time: std::time::Duration, parameter_file: impl AsRef<Path>, start: impl FnOnce(&Self)) -> Result<Self, ParameterError> {
+        let parameters = Arc::new(ParameterSet::from_file(parameter_file)?);
+        let group = Self::create_main_group(name, cycle_time, Some(Arc::clone(&parameters)));
+        if let Err(error) = parameters.check() {
+            // the threads of the modules exit without calling any hook
+            group.shutdown();
+            group.parent.start_gate.open();
+            return Err(error);
+        }
+        start(&group);
+        Ok(group)
+    }
+
+    /// Returns the network of a main group without starting its modules or the TCP server of the monitor,
+    /// e.g. to export it while the system is running elsewhere. The group is shut down before this returns.
+    pub fn main_group_network(name: &str, cycle_time: std::time::Duration) -> Network {
+        let group = Self::create_main_group(name, cycle_time, None);
+        let network = group.network();
+        group.shutdown();
+        group.parent.start_gate.open();
+        network
+    }
+
+    /// Initializes the main group. The threads of its modules wait for [`BehaviorGroup::start`].
+    fn create_main_group(name: &str, cycle_time: std::time::Duration, parameters: Option<Arc<ParameterSet>>) -> Self {
         println!("Initializing  Main BehaviorGroup: {}", name);
-        let tcp_server = TcpServer::new();
-        tcp_server.start(); 
         let parent = Parent {
             path: name.to_string(),
-            tcp_server,
+            supervisor: Supervisor::new(M::RESTART_STRATEGY, None).map(Arc::new),
+            parameters,
+            start_gate: StartGate::closed(),
+            ..Default::default()
         };
         let mut group = M::default();
-        group.init(cycle_time, &parent);
+        Self::initialize(&mut group, cycle_time, &parent);
         Self {
             module: group,
+            parent,
+            cycle_time,
+        }
+    }
+
+    /// Starts the TCP server for the monitor and the threads of all modules of the main group.
+    fn start(&self) {
+        self.parent.tcp_server.start(Arc::clone(&self.parent.live_parameters));
+        self.parent.start_gate.open();
+    }
+
+    /// Calls [`Group::init`] and starts the aggregation of the members of the group and all modules registered with [`Parent::spawn`].
+    fn initialize(group: &mut M, cycle_time: std::time::Duration, parent: &Parent) {
+        group.init(cycle_time, parent);
+        let members = std::mem::take(group.members());
+        if !members.is_empty() {
+            parent.spawn(MemberAggregation {
+                members,
+                aggregation: M::ACTIVITY_AGGREGATION,
+                activity: group.get_activity_port().clone(),
+                target_rating: group.get_target_rating_port().clone(),
+                cycle_time,
+                stop: Arc::clone(&parent.stop),
+                start_gate: parent.start_gate.clone(),
+            });
+        }
+        parent.spawn_pending();
+        parent.network.register(&parent.path, NodeKind::Group, group.port_links());
+    }
+
+    pub fn cycle_time(&self) -> std::time::Duration {
+        self.cycle_time
+    }
+
+    /// Returns the current parameter values of all modules of the system this group belongs to.
+    pub fn current_parameters(&self) -> ParameterSet {
+        self.parent.live_parameters.snapshot()
+    }
+
+    /// Saves the current parameter values of all modules of the system this group belongs to.
+    /// The file can be loaded again with [`SpawnMainGroup!`][crate::SpawnMainGroup], its format is determined by the file extension.
+    /// The monitor can save the parameters with [`MonitorCommand::SaveParameters`][rust_ib2c_shared_data::MonitorCommand].
+    pub fn save_parameters(&self, path: impl AsRef<Path>) -> Result<(), ParameterError> {
+        self.current_parameters().save(path)
+    }
+
+    /// Returns the modules, fusion modules and subgroups of this group and the connections between them.
+    /// Only spawned modules are included. Connections made after the call are not part of the returned network.
+    pub fn network(&self) -> Network {
+        self.parent.network.network(&self.parent.path)
+    }
+
+    /// Renders the network of this group as a Graphviz DOT graph, see [`Network::to_dot`].
+    pub fn to_dot(&self) -> String {
+        self.network().to_dot()
+    }
+
+    /// Renders the network of this group as a Mermaid flowchart, see [`Network::to_mermaid`].
+    pub fn to_mermaid(&self) -> String {
+        self.network().to_mermaid()
+    }
+
+    /// Stops all modules of the system this group belongs to.
+    /// Every module calls [`Module::on_stop`] before its thread exits.
+    pub fn shutdown(&self) {
+        self.parent.request_stop();
+    }
+}
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::{AtomicUsize, Ordering}, time::Duration};
+
+    use crate::{network::{EdgeKind, NodeKind}, prelude::*, wait_until};
+
+    static HEALTHY_STARTS: AtomicUsize = AtomicUsize::new(0);
+
+    #[module]
+    struct Healthy {}
+
+    impl Module for Healthy {
+        fn transfere(&mut self) {}
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+
+        fn on_start(&mut self) {
+            HEALTHY_STARTS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[module]
+    struct Crashing {
+        cycles: u32,
+    }
+
+    impl Module for Crashing {
+        fn transfere(&mut self) {
+            self.cycles += 1;
+            if self.cycles == 3 {
+                panic!("third cycle");
+            }
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[group]
+    struct AllForOneGroup {}
+
+    impl Group for AllForOneGroup {
+        const RESTART_STRATEGY: RestartStrategy = RestartStrategy::AllForOne {
+            max_restarts: 1000,
+            period: Duration::from_secs(1),
+        };
+
+        #[spawn]
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            let _healthy = SpawnModule!(Healthy, "Healthy");
+            let _crashing = SpawnModule!(Crashing, "Crashing");
+        }
+    }
+
+    #[group]
+    struct IntensityGroup {}
+
+    impl Group for IntensityGroup {
+        const RESTART_STRATEGY: RestartStrategy = RestartStrategy::OneForOne {
+            max_restarts: 2,
+            period: Duration::from_secs(10),
+        };
+
+        #[spawn]
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            let _crashing = SpawnModule!(Crashing, "Crashing");
+        }
+    }
+
+    static FRAGILE_STARTS: AtomicUsize = AtomicUsize::new(0);
+
+    #[module]
+    struct Fragile {
+        cycles: u32,
+    }
+
+    impl Module for Fragile {
+        fn transfere(&mut self) {
+            self.cycles += 1;
+            if self.cycles == 3 {
+                panic!("third cycle");
+            }
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+
+        fn on_start(&mut self) {
+            FRAGILE_STARTS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[group]
+    struct FragileGroup {}
+
+    impl Group for FragileGroup {
+        const RESTART_STRATEGY: RestartStrategy = RestartStrategy::OneForOne {
+            max_restarts: 0,
+            period: Duration::from_secs(10),
+        };
+
+        #[spawn]
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            let _fragile = SpawnModule!(Fragile, "Fragile");
+        }
+    }
+
+    #[group]
+    struct EscalationGroup {}
+
+    impl Group for EscalationGroup {
+        const RESTART_STRATEGY: RestartStrategy = RestartStrategy::OneForOne {
+            max_restarts: 1000,
+            period: Duration::from_secs(1),
+        };
+
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            let _fragile = BehaviorGroup::<FragileGroup>::with_name("FragileGroup", cycle_time, parent);
+        }
+    }
+
+    #[module(cycle_time = "20ms")]
+    struct Planner {
+        pub out_plan: SendPort<u32>,
+    }
+
+    impl Module for Planner {
+        fn transfere(&mut self) {}
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[group]
+    struct PlanningGroup {}
+
+    impl Group for PlanningGroup {
+        #[spawn]
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            let planner = SpawnModule!(Planner, "Planner");
+            let fast_planner = SpawnModule!(Planner, "FastPlanner", Duration::from_millis(1));
+            let _fusion = SpawnFusion!(MaximumFusion, "Fusion", inputs: [planner.out_plan, fast_planner.out_plan]);
+        }
+    }
+
+    #[module]
+    struct Increment {
+        pub in_value: ReceivePort<u32>,
+        pub out_value: SendPort<u32>,
+    }
+
+    impl Module for Increment {
+        fn transfere(&mut self) {
+            if let Some(value) = self.in_value.get() {
+                self.out_value.send(value + 1);
+            }
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[module]
+    struct Constant {
+        pub out_value: SendPort<u32>,
+    }
+
+    impl Module for Constant {
+        fn transfere(&mut self) {
+            self.out_value.send(7);
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[group]
+    struct NetworkGroup {
+        pub in_value: ReceivePort<u32>,
+        pub out_value: SendPort<u32>,
+    }
+
+    impl Group for NetworkGroup {
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            network! {
+                module constant: Constant("Constant").set_control_mode(ControlMode::WhenActive);
+                module increment: Increment("Increment", Duration::from_millis(1));
+                fusion fusion: MaximumFusion("Fusion") <- [constant.out_value, increment.out_value];
+
+                self.in_value -> increment.in_value;
+                fusion.output -> self.out_value;
+                increment inhibits constant;
+                characteristic fusion;
+            }
+        }
+    }
+
+    #[group]
+    struct SensorArray {
+        pub in_value: ReceivePort<u32>,
+        pub out_value: SendPort<u32>,
+    }
+
+    fn spawn_filter(index: usize, input: &ReceivePort<u32>, fusion: &mut MaximumFusion<u32>, cycle_time: Duration, parent: &Parent) {
+        let filter = BehaviorModule::<Increment>::with_name(&format!("Filter{}", index), cycle_time, parent);
+        filter.in_value.connect_to_source(input).unwrap();
+        fusion.connect_module(&*filter, &filter.out_value).unwrap();
+        parent.spawn(filter);
+    }
+
+    impl Group for SensorArray {
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            let mut fusion = MaximumFusion::with_name("Fusion", cycle_time, parent);
+            for index in 0..3 {
+                spawn_filter(index, &self.in_value, &mut fusion, cycle_time, parent);
+            }
+            self.out_value.connect_to_source(&fusion.output).unwrap();
+            self.set_characteristic_module(&mut fusion).unwrap();
+            parent.spawn(fusion);
+        }
+    }
+
+    #[test]
+    fn test_registered_spawn() {
+        let parent = Parent::default();
+        let group = BehaviorGroup::<SensorArray>::with_name("Sensors", Duration::from_millis(1), &parent);
+        let input = SendPort::default();
+        group.in_value.connect_to_source(&input).unwrap();
+        input.send(1);
+        assert!(wait_until(|| group.out_value.get() == Some(2)));
+        assert!(wait_until(|| group.activity.get() == Some(MetaSignal::HIGH)));
+        parent.request_stop();
+    }
+
+    #[test]
+    fn test_network() {
+        let parent = Parent::default();
+        let group = BehaviorGroup::<NetworkGroup>::with_name("Network", Duration::from_millis(1), &parent);
+        let input = SendPort::default();
+        group.in_value.connect_to_source(&input).unwrap();
+        input.send(41);
+        // the constant module would win the fusion if it was not inhibited
+        assert!(wait_until(|| group.out_value.get() == Some(42)));
+        parent.request_stop();
+    }
+
+    #[group]
+    struct ExportGroup {}
+
+    impl Group for ExportGroup {
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            network! {
+                module source: Constant("Source");
+                group inner: NetworkGroup("Inner");
+
+                source.out_value -> inner.in_value;
+                source stimulates inner;
+            }
+        }
+    }
+
+    #[test]
+    fn test_network_export() {
+        let parent = Parent { path: "Main".to_string(), ..Default::default() };
+        let group = BehaviorGroup::<ExportGroup>::with_name("Export", Duration::from_millis(1), &parent);
+        let network = group.network();
+        parent.request_stop();
+
+        let mut nodes: Vec<_> = network.nodes.iter().map(|node| (node.path.as_str(), node.kind)).collect();
+        nodes.sort();
+        assert_eq!(nodes, [
+            ("Main/Export", NodeKind::Group),
+            ("Main/Export/Inner", NodeKind::Group),
+            ("Main/Export/Inner/Constant", NodeKind::Module),
+            ("Main/Export/Inner/Fusion", NodeKind::Fusion),
+            ("Main/Export/Inner/Increment", NodeKind::Module),
+            ("Main/Export/Source", NodeKind::Module),
+        ]);
+
+        let mut edges: Vec<_> = network.edges.iter()
+            .map(|edge| (network.nodes[edge.source].name(), edge.source_port.as_str(), network.nodes[edge.target].name(), edge.target_port.as_str(), edge.kind))
+            .collect();
+        edges.sort_by_key(|edge| format!("{:?}", edge));
+        // the stimulation of the group is passed through to its characteristic fusion module,
+        // the activity and target rating inputs of the fusion module are not listed
+        assert_eq!(edges, [
+            ("Constant", "out_value", "Fusion", "data_ports[0]", EdgeKind::Data),
+            ("Fusion", "output", "Inner", "out_value", EdgeKind::Data),
+            ("Increment", "activity", "Constant", "inhibition", EdgeKind::Inhibition),
+            ("Increment", "out_value", "Fusion", "data_ports[1]", EdgeKind::Data),
+            ("Inner", "in_value", "Increment", "in_value", EdgeKind::Data),
+            ("Source", "activity", "Fusion", "stimulation", EdgeKind::Stimulation),
+            ("Source", "out_value", "Inner", "in_value", EdgeKind::Data),
+        ]);
+
+        let dot = group.to_dot();
+        assert!(dot.starts_with("digraph network {"));
+        assert_eq!(dot.matches("subgraph cluster_").count(), 2);
+        assert_eq!(dot.matches("arrowhead=tee").count(), 1);
+        assert!(dot.contains("[label=\"Fusion\\n(max)\", shape=hexagon];"));
+
+        let mermaid = group.to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert_eq!(mermaid.matches("subgraph ").count(), 2);
+        assert_eq!(mermaid.matches("-.-x|inhibition|").count(), 1);
+        assert_eq!(mermaid.matches("-.->|stimulation|").count(), 1);
+    }
+
+    #[module]
+    struct Weak {}
+
+    impl Module for Weak {
+        fn transfere(&mut self) {}
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::new(0.25)
         }
     }
-}
\ No newline at end of file
+
+    #[module]
+    struct Medium {}
+
+    impl Module for Medium {
+        fn transfere(&mut self) {}
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::new(0.5)
+        }
+    }
+
+    #[group]
+    struct MaximumGroup {}
+
+    impl Group for MaximumGroup {
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            network! {
+                module weak: Weak("Weak");
+                module medium: Medium("Medium");
+                member weak;
+                member medium;
+            }
+        }
+    }
+
+    #[group]
+    struct SumGroup {}
+
+    impl Group for SumGroup {
+        const ACTIVITY_AGGREGATION: ActivityAggregation = ActivityAggregation::Sum;
+
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            network! {
+                module weak: Weak("Weak");
+                module medium: Medium("Medium");
+                member weak;
+                member medium;
+            }
+        }
+    }
+
+    #[test]
+    fn test_member_aggregation() {
+        let parent = Parent::default();
+        let maximum = BehaviorGroup::<MaximumGroup>::with_name("Maximum", Duration::from_millis(1), &parent);
+        let sum = BehaviorGroup::<SumGroup>::with_name("Sum", Duration::from_millis(1), &parent);
+        assert!(wait_until(|| maximum.activity.get() == Some(MetaSignal::new(0.5))));
+        assert!(wait_until(|| maximum.target_rating.get() == Some(MetaSignal::new(0.5))));
+        assert!(wait_until(|| sum.activity.get() == Some(MetaSignal::new(0.75))));
+        assert!(wait_until(|| sum.target_rating.get().is_some_and(|target_rating| (*target_rating - (0.0625 + 0.25) / 0.75).abs() < 1e-6)));
+
+        // the inhibition of the group reaches all members
+        let inhibition = SendPort::default();
+        inhibition.send(MetaSignal::HIGH);
+        maximum.inhibition.connect_to_source(&inhibition).unwrap();
+        assert!(wait_until(|| maximum.activity.get() == Some(MetaSignal::LOW)));
+        parent.request_stop();
+    }
+
+    #[test]
+    fn test_member_and_characteristic_module() {
+        let mut group = MaximumGroup::default();
+        let mut weak = Weak::default();
+        let mut medium = Medium::default();
+        group.set_characteristic_module(&mut weak).unwrap();
+        assert_eq!(group.add_member(&mut medium), Err(ConnectionError::AlreadyConnected));
+
+        let mut group = MaximumGroup::default();
+        group.add_member(&mut Weak::default()).unwrap();
+        assert_eq!(group.set_characteristic_module(&mut medium), Err(ConnectionError::AlreadyConnected));
+    }
+
+    #[test]
+    fn test_declared_cycle_time() {
+        assert_eq!(Planner::CYCLE_TIME, Some(Duration::from_millis(20)));
+        assert_eq!(Healthy::CYCLE_TIME, None);
+
+        let parent = Parent::default();
+        let _group = BehaviorGroup::<PlanningGroup>::with_name("Planning", Duration::from_millis(1), &parent);
+        parent.request_stop();
+    }
+
+    #[test]
+    #[should_panic(expected = "longer than the cycle time")]
+    fn test_slow_fusion_module() {
+        let parent = Parent::default();
+        let _group = BehaviorGroup::<PlanningGroup>::with_name("Planning", Duration::from_millis(10), &parent);
+    }
+
+    #[test]
+    fn test_all_for_one_restart() {
+        let parent = Parent::default();
+        let _group = BehaviorGroup::<AllForOneGroup>::with_name("AllForOne", Duration::from_millis(1), &parent);
+        assert!(wait_until(|| HEALTHY_STARTS.load(Ordering::SeqCst) > 1));
+        assert!(!parent.stop_requested());
+        parent.request_stop();
+    }
+
+    #[test]
+    fn test_main_group_network() {
+        let network = BehaviorGroup::<ExportGroup>::main_group_network("Export", Duration::from_millis(1));
+        let mut nodes: Vec<_> = network.nodes.iter().map(|node| node.path.as_str()).collect();
+        nodes.sort();
+        assert_eq!(nodes, ["Export", "Export/Inner", "Export/Inner/Constant", "Export/Inner/Fusion", "Export/Inner/Increment", "Export/Source"]);
+    }
+
+    static TUNED_STARTS: AtomicUsize = AtomicUsize::new(0);
+
+    #[module]
+    struct Tuned {
+        par_gain: ParameterPort<f64>,
+    }
+
+    impl Module for Tuned {
+        fn transfere(&mut self) {}
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+
+        fn on_start(&mut self) {
+            TUNED_STARTS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[group]
+    struct TunedGroup {}
+
+    impl Group for TunedGroup {
+        #[spawn]
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            let _tuned = SpawnModule!(Tuned, "Tuned");
+        }
+    }
+
+    #[test]
+    fn test_invalid_parameter_file() {
+        let directory = std::env::temp_dir();
+        let invalid_file = directory.join(format!("rust_ib2c_invalid_{}.toml", std::process::id()));
+        let valid_file = directory.join(format!("rust_ib2c_valid_{}.toml", std::process::id()));
+        std::fs::write(&invalid_file, "[\"Main/Unknown\"]\npar_gain = 2.0\n").unwrap();
+        std::fs::write(&valid_file, "[\"Main/Tuned\"]\npar_gain = 2.0\n").unwrap();
+
+        // the modules are started without the server of the monitor
+        let start = |group: &BehaviorGroup<TunedGroup>| group.parent.start_gate.open();
+        let result = BehaviorGroup::<TunedGroup>::load_main_group("Main", Duration::from_millis(1), &invalid_file, |_| panic!("group with invalid parameters started"));
+        assert_eq!(result.err(), Some(ParameterError::UnknownModule("Main/Unknown".to_string())));
+        assert_eq!(TUNED_STARTS.load(Ordering::SeqCst), 0);
+
+        let group = BehaviorGroup::<TunedGroup>::load_main_group("Main", Duration::from_millis(1), &valid_file, start).unwrap();
+        assert!(wait_until(|| TUNED_STARTS.load(Ordering::SeqCst) == 1));
+        assert_eq!(group.current_parameters().get("Main/Tuned", "par_gain"), Some(&ParameterValue::from(2.0)));
+        group.shutdown();
+        let _ = std::fs::remove_file(invalid_file);
+        let _ = std::fs::remove_file(valid_file);
+    }
+
+    #[test]
+    fn test_restart_intensity() {
+        let parent = Parent::default();
+        let _group = BehaviorGroup::<IntensityGroup>::with_name("Intensity", Duration::from_millis(1), &parent);
+        assert!(wait_until(|| parent.stop_requested()));
+    }
+
+    #[test]
+    fn test_restart_escalation() {
+        let parent = Parent::default();
+        let _group = BehaviorGroup::<EscalationGroup>::with_name("Escalation", Duration::from_millis(1), &parent);
+        // the inner group does not allow any restart, so every fault is handled by the enclosing group
+        assert!(wait_until(|| FRAGILE_STARTS.load(Ordering::SeqCst) > 2));
+        assert!(!parent.stop_requested());
+        parent.request_stop();
+    }
+}
@@ -1,10 +1,258 @@
+use data_types::si_units::parse_in_base_units;
 use proc_macro::TokenStream;
+
 use quote::quote;
-use syn::{parse_macro_input, Fields, ItemFn, ItemStruct, Pat, Stmt, Type};
+use syn::{parse::Parser, parse_macro_input, parse_quote, punctuated::Punctuated, token::Comma, Data, DeriveInput, Expr, Field, Fields, GenericArgument, ItemFn, ItemStruct, Lit, Meta, Pat, PathArguments, Stmt, Type};
+
+mod network;
+
+/// Returns the name of the port type of a field (`ReceivePort`, `SendPort` or `ParameterPort`) if the field is a port.
+/// Ports inside `Option`s, `Vec`s and arrays are detected as well.
+fn port_type(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            let name = segment.ident.to_string();
+            match name.as_str() {
+                "ReceivePort" | "SendPort" | "ParameterPort" => Some(name),
+                "Option" | "Vec" => {
+                    if let PathArguments::AngleBracketed(arguments) = &segment.arguments
+                        && let Some(GenericArgument::Type(inner)) = arguments.args.first()
+                    {
+                        port_type(inner)
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            }
+        }
+        Type::Array(array) => port_type(&array.elem),
+        _ => None,
+    }
+}
+
+/// Returns true if the type is a `ParameterPort`. Parameters in collections can not be loaded by name.
+fn is_parameter_port(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| segment.ident == "ParameterPort"))
+}
+
+/// Returns true if the field is a port, a collection of ports or marked with `#[port]`.
+fn is_port_field(field: &Field) -> bool {
+    port_type(&field.ty).is_some() || field.attrs.iter().any(|attr| attr.path().is_ident("port"))
+}
+
+/// Options of the #[module] and #[group] macros.
+struct MacroOptions {
+    naming_check: bool,
+    /// Declared cycle time in nanoseconds.
+    cycle_time: Option<u64>,
+}
+
+/// Parses the options of the #[module] and #[group] macros.
+/// `cycle_time` is only accepted if `allow_cycle_time` is set.
+fn parse_options(attr: TokenStream, allow_cycle_time: bool) -> syn::Result<MacroOptions> {
+    let mut options = MacroOptions { naming_check: true, cycle_time: None };
+    let expected = if allow_cycle_time { 
+        "unknown option, expected `skip_naming_check` or `cycle_time = \"...\"`" 
+    } else { 
+        "unknown option, expected `skip_naming_check`" 
+    };
+    for meta in Punctuated::<Meta, Comma>::parse_terminated.parse(attr)? {
+        match &meta {
+            Meta::Path(path) if path.is_ident("skip_naming_check") => options.naming_check = false,
+            Meta::NameValue(name_value) if allow_cycle_time && name_value.path.is_ident("cycle_time") => {
+                let Expr::Lit(syn::ExprLit { lit: Lit::Str(value), .. }) = &name_value.value else {
+                    return Err(syn::Error::new_spanned(&name_value.value, "expected a duration like \"20ms\""));
+                };
+                options.cycle_time = Some(parse_duration(&value.value())
+                    .ok_or_else(|| syn::Error::new_spanned(value, "invalid duration, expected a number followed by `ns`, `us`, `ms` or `s`"))?);
+            }
+            _ => return Err(syn::Error::new_spanned(&meta, expected)),
+        }
+    }
+    Ok(options)
+}
+
+/// Parses durations like "20ms", "1.5 s" or "500us" into nanoseconds.
+fn parse_duration(duration: &str) -> Option<u64> {
+    let duration = duration.trim();
+    let unit_start = duration.find(|c: char| c.is_ascii_alphabetic())?;
+    let (value, unit) = duration.split_at(unit_start);
+    let value: f64 = value.trim().parse().ok()?;
+    let nanos_per_unit = match unit {
+        "ns" => 1.0,
+        "us" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        _ => return None,
+    };
+    (value > 0.0).then(|| (value * nanos_per_unit).round() as u64)
+}
+
+/// Parses `#[parameter(...)]` of a field into a `ParameterDeclaration` expression.
+/// Limits and step size can be any expression convertible to a `ParameterValue`, e.g. `min = "10 cm"` or `max = 1.5`.
+/// Literal values are checked here, whether they fit the type of the parameter is checked when the module is created.
+fn parse_parameter_declaration(field: &Field) -> syn::Result<Option<proc_macro2::TokenStream>> {
+    let Some(attr) = field.attrs.iter().find(|attr| attr.path().is_ident("parameter")) else {
+        return Ok(None);
+    };
+    if !is_parameter_port(&field.ty) {
+        return Err(syn::Error::new_spanned(attr, "#[parameter] can only be used on `ParameterPort` fields"));
+    }
+    let [mut min, mut max, mut step, mut description, mut unit] = std::array::from_fn(|_| quote! { None });
+    let [mut min_value, mut max_value, mut step_value] = [None; 3];
+    let mut unit_value = None;
+    let options = attr.parse_args_with(Punctuated::<Meta, Comma>::parse_terminated)?;
+    for meta in &options {
+        let Meta::NameValue(name_value) = meta else {
+            return Err(syn::Error::new_spanned(meta, "expected `name = value`"));
+        };
+        let value = &name_value.value;
+        let text = match value {
+            Expr::Lit(syn::ExprLit { lit: Lit::Str(text), .. }) => Ok(quote! { Some(#text) }),
+            _ => Err(syn::Error::new_spanned(value, "expected a string")),
+        };
+        match name_value.path.get_ident() {
+            Some(name) if name == "min" => {
+                min_value = literal_value(value)?.map(|literal| (value, literal));
+                min = quote! { Some(ParameterValue::from(#value)) };
+            }
+            Some(name) if name == "max" => {
+                max_value = literal_value(value)?.map(|literal| (value, literal));
+                max = quote! { Some(ParameterValue::from(#value)) };
+            }
+            Some(name) if name == "step" => {
+                step_value = literal_value(value)?.map(|literal| (value, literal));
+                step = quote! { Some(ParameterValue::from(#value)) };
+            }
+            Some(name) if name == "description" => description = text?,
+            Some(name) if name == "unit" => {
+                if let Expr::Lit(syn::ExprLit { lit: Lit::Str(symbol), .. }) = value {
+                    let (_, dimension) = parse_in_base_units(&format!("1 {}", symbol.value()))
+                        .map_err(|error| syn::Error::new_spanned(symbol, format!("invalid unit: {}", error)))?;
+                    unit_value = Some((value, dimension));
+                }
+                unit = text?;
+            }
+            _ => return Err(syn::Error::new_spanned(&name_value.path, "unknown option, expected `min`, `max`, `step`, `unit` or `description`")),
+        }
+    }
+    check_parameter_literals(min_value, max_value, step_value, unit_value)?;
+    Ok(Some(quote! {
+        ParameterDeclaration { min: #min, max: #max, step: #step, description: #description, unit: #unit }
+    }))
+}
+
+/// Value in SI base units and dimension of a literal limit or step size, e.g. `1.5`, `-2` or `"10 cm"`.
+/// Returns `None` for other expressions, they are only checked when the module is created.
+fn literal_value(value: &Expr) -> syn::Result<Option<(f64, [i32; 7])>> {
+    let invalid = |error: &dyn std::fmt::Display| syn::Error::new_spanned(value, format!("invalid value: {}", error));
+    match value {
+        Expr::Lit(syn::ExprLit { lit: Lit::Int(number), .. }) => number.base10_parse::<f64>().map(|number| Some((number, [0; 7]))),
+        Expr::Lit(syn::ExprLit { lit: Lit::Float(number), .. }) => number.base10_parse::<f64>().map(|number| Some((number, [0; 7]))),
+        Expr::Lit(syn::ExprLit { lit: Lit::Str(text), .. }) => parse_in_base_units(&text.value()).map(Some).map_err(|error| invalid(&error)),
+        Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) => {
+            Ok(literal_value(expr)?.map(|(number, dimension)| (-number, dimension)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Checks that the literal limits, step size and unit of a `#[parameter(...)]` have the same dimension,
+/// the minimum is not greater than the maximum and the step size is positive.
+fn check_parameter_literals(
+    min: Option<(&Expr, (f64, [i32; 7]))>,
+    max: Option<(&Expr, (f64, [i32; 7]))>,
+    step: Option<(&Expr, (f64, [i32; 7]))>,
+    unit: Option<(&Expr, [i32; 7])>,
+) -> syn::Result<()> {
+    let dimensions = [min, max, step].into_iter().flatten().map(|(value, (_, dimension))| (value, dimension)).chain(unit);
+    let mut expected = None;
+    for (value, dimension) in dimensions {
+        match expected {
+            None => expected = Some(dimension),
+            Some(expected) if expected != dimension => {
+                return Err(syn::Error::new_spanned(value, "the limits, step size and unit of a parameter must have the same dimension"));
+            }
+            Some(_) => {}
+        }
+    }
+    if let (Some((_, (min, _))), Some((max_expr, (max, _)))) = (min, max)
+        && min > max
+    {
+        return Err(syn::Error::new_spanned(max_expr, "`max` must not be less than `min`"));
+    }
+    if let Some((step_expr, (step, _))) = step
+        && step <= 0.0
+    {
+        return Err(syn::Error::new_spanned(step_expr, "`step` must be positive"));
+    }
+    Ok(())
+}
+
+/// Checks that fields prefixed with `in_`, `out_` and `par_` are `ReceivePort`s, `SendPort`s and `ParameterPort`s.
+fn check_port_naming(fields: &Punctuated<Field, Comma>) -> proc_macro2::TokenStream {
+    let mut errors = proc_macro2::TokenStream::new();
+    for field in fields {
+        let Some(name) = &field.ident else {
+            continue;
+        };
+        let name = name.to_string();
+        let expected = if name.starts_with("in_") {
+            "ReceivePort"
+        } else if name.starts_with("out_") {
+            "SendPort"
+        } else if name.starts_with("par_") {
+            "ParameterPort"
+        } else {
+            continue;
+        };
+        let port = port_type(&field.ty);
+        // the type of fields marked with #[port] can not be determined from their name
+        if port.is_none() && is_port_field(field) {
+            continue;
+        }
+        if port.as_deref() != Some(expected) {
+            let message = format!(
+                "`{}` must be a `{}` because of its prefix. Rename the field or use `skip_naming_check` to disable this check.", 
+                name, expected
+            );
+            errors.extend(syn::Error::new_spanned(&field.ty, message).to_compile_error());
+        }
+    }
+    errors
+}
 
 /// Automatically ports (activity, target_rating, stimulation, inhibition) to a struct
+/// 
+/// Fields prefixed with `in_`, `out_` and `par_` have to be `ReceivePort`s, `SendPort`s and `ParameterPort`s.
+/// Use `#[module(skip_naming_check)]` to disable this check.
+/// 
+/// The natural cycle time of the module can be declared with `#[module(cycle_time = "20ms")]`.
+/// It is used instead of the cycle time of the group unless a cycle time is passed to `SpawnModule!`.
+/// 
+/// Ports and `Option`s, `Vec`s and arrays of ports are detected by their type. 
+/// Mark fields with `#[port]` if their type is an alias or re-export of a port type.
+/// 
+/// Limits and display hints of parameters are declared with `#[parameter(...)]`:
+/// ```rust ignore
+/// #[parameter(min = "0 m", max = "5 m", step = "10 cm", unit = "cm", description = "Distance to stop in front of obstacles")]
+/// par_min_distance: ParameterPort<Distance>,
+/// ```
+/// Values outside of the limits are rejected by `ParameterPort::set`, all options are sent to the monitor.
+/// Invalid numbers and units, limits of different dimensions, `min` greater than `max` and non-positive steps are compile errors.
 #[proc_macro_attribute]
-pub fn module(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn module(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let MacroOptions { naming_check, cycle_time } = match parse_options(attr, true) {
+        Ok(options) => options,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let cycle_time = match cycle_time {
+        Some(nanos) => quote! { Some(std::time::Duration::from_nanos(#nanos)) },
+        None => quote! { None },
+    };
+
     // Parse the input as a struct
     let input = parse_macro_input!(item as ItemStruct);
     let struct_name = input.ident.clone();
@@ -15,7 +263,7 @@ pub fn module(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let generics = input.generics.clone();
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let fields = if let Fields::Named(fields_named) = input.fields.clone() {
+    let mut fields = if let Fields::Named(fields_named) = input.fields.clone() {
         fields_named.named
     } else {
         return TokenStream::from(quote! {
@@ -23,29 +271,42 @@ pub fn module(_attr: TokenStream, item: TokenStream) -> TokenStream {
         });
     };
 
-    let all_port_names: Vec<_> = fields.iter().filter_map(|field| {
-        if let Type::Path(type_path) = &field.ty {
-            if let Some(ident) = type_path.path.segments.last().map(|s| &s.ident) {
-                if ident == "ReceivePort" || ident == "SendPort" {
-                    return field.ident.clone();
-                }
+    let mut declared_parameters = Vec::new();
+    let mut parameter_declarations = Vec::new();
+    for field in &fields {
+        match parse_parameter_declaration(field) {
+            Ok(Some(declaration)) => {
+                declared_parameters.push(field.ident.clone());
+                parameter_declarations.push(declaration);
             }
+            Ok(None) => {}
+            Err(error) => return error.to_compile_error().into(),
         }
-        None
-    }).collect();
+    }
+    // #[parameter] is only read by this macro and has to be removed from the struct
+    for field in fields.iter_mut() {
+        field.attrs.retain(|attr| !attr.path().is_ident("parameter"));
+    }
 
-    let parameter_port_names: Vec<_> = fields.iter().filter_map(|field| {
-        if let Type::Path(type_path) = &field.ty {
-            if let Some(ident) = type_path.path.segments.last().map(|s| &s.ident) {
-                if ident == "ParameterPort" {
-                    return field.ident.clone();
-                }
-            }
-        }
-        None
-    }).collect();
+    let port_names: Vec<_> = fields.iter()
+        .filter(|field| is_port_field(field))
+        .filter_map(|field| field.ident.clone())
+        .collect();
+
+    let parameter_names: Vec<_> = fields.iter()
+        .filter(|field| is_parameter_port(&field.ty))
+        .filter_map(|field| field.ident.clone())
+        .collect();
+
+    let naming_errors = if naming_check {
+        check_port_naming(&fields)
+    } else {
+        proc_macro2::TokenStream::new()
+    };
 
     let expanded = quote! {
+        #naming_errors
+
         #(#attrs)*
         #[ports]
         #vis struct #struct_name #generics
@@ -57,30 +318,97 @@ pub fn module(_attr: TokenStream, item: TokenStream) -> TokenStream {
         impl #impl_generics PortParsing for #struct_name #ty_generics
         #where_clause
         {
-            fn all_port_data(&self) -> Vec<(&'static str, PortData)> {
+            fn all_port_data(&self) -> Vec<(String, PortDirection, PortData)> {
                 let mut port_data = Vec::new();
                 #(
-                    let param = self.#parameter_port_names.get();
-                    port_data.push((stringify!(#parameter_port_names), param.serialize_port_data()));
-                )*
-                #(
-                    if let Some(port_data_item) = self.#all_port_names.get() {
-                        port_data.push((stringify!(#all_port_names), port_data_item.serialize_port_data()));
-                    } 
+                    PortField::serialize_port(&self.#port_names, stringify!(#port_names), &mut port_data);
                 )*
                 port_data
             }
         }
-    };
 
+        impl #impl_generics ParameterLoading for #struct_name #ty_generics
+        #where_clause
+        {
+            fn load_parameter(&self, name: &str, value: &ParameterValue) -> Option<Result<(), String>> {
+                match name {
+                    #(
+                        stringify!(#parameter_names) => Some((&ParameterAccess(&self.#parameter_names)).load_value(value)),
+                    )*
+                    _ => None,
+                }
+            }
+
+            fn parameter_readers(&self) -> Vec<(&'static str, ParameterReader)> {
+                let mut parameter_readers = Vec::new();
+                #(
+                    if let Some(reader) = (&ParameterAccess(&self.#parameter_names)).reader() {
+                        parameter_readers.push((stringify!(#parameter_names), reader));
+                    }
+                )*
+                parameter_readers
+            }
+
+            fn declare_parameters(&mut self) -> Result<(), String> {
+                #(
+                    self.#declared_parameters.declare(#parameter_declarations)
+                        .map_err(|error| format!("{}: {}", stringify!(#declared_parameters), error))?;
+                )*
+                Ok(())
+            }
+
+            fn parameter_info(&self) -> Vec<(String, ParameterInfo)> {
+                let mut parameter_info = Vec::new();
+                #(
+                    if let Some(info) = self.#declared_parameters.info() {
+                        parameter_info.push((stringify!(#declared_parameters).to_string(), info.clone()));
+                    }
+                )*
+                parameter_info
+            }
 
+            fn parameter_names(&self) -> Vec<&'static str> {
+                vec![#(stringify!(#parameter_names)),*]
+            }
+
+            fn changed_parameters(&self) -> Vec<&'static str> {
+                let mut changed_parameters = Vec::new();
+                #(
+                    if self.#parameter_names.changed() {
+                        changed_parameters.push(stringify!(#parameter_names));
+                    }
+                )*
+                changed_parameters
+            }
+        }
+
+        impl #impl_generics ModuleAttributes for #struct_name #ty_generics
+        #where_clause
+        {
+            const DECLARED_CYCLE_TIME: Option<std::time::Duration> = #cycle_time;
+        }
+    };
 
     TokenStream::from(expanded)
 }
 
 /// Adds standard ports (activity, target_rating, stimulation, inhibition) and implements MetaSignals and UpdateReceivePorts
+/// 
+/// Fields prefixed with `in_`, `out_` and `par_` have to be `ReceivePort`s, `SendPort`s and `ParameterPort`s.
+/// Use `#[group(skip_naming_check)]` to disable this check.
+/// 
+/// The meta signals of the group are connected in `init` with one of:
+/// - `set_characteristic_module(&mut module)`: activity and target rating are passed through from the module,
+///   stimulation and inhibition of the group are passed to it.
+/// - `add_member(&mut module)` for each of several modules: activity and target rating are aggregated from all members
+///   according to `Group::ACTIVITY_AGGREGATION`, stimulation and inhibition of the group are passed to every member.
 #[proc_macro_attribute]
-pub fn group(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn group(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let naming_check = match parse_options(attr, false) {
+        Ok(options) => options.naming_check,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
     // Parse the input as a struct
     let input = parse_macro_input!(item as ItemStruct);
     let struct_name = input.ident.clone();
@@ -99,27 +427,58 @@ pub fn group(_attr: TokenStream, item: TokenStream) -> TokenStream {
         });
     };
 
+    let naming_errors = if naming_check {
+        check_port_naming(&fields)
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
     let expanded = quote! {
+        #naming_errors
         #(#attrs)*
         #[ports]
         #vis struct #struct_name #generics
         #where_clause
         {
             #fields
+            members: GroupMembers,
+        }
+
+        impl #impl_generics Members for #struct_name #ty_generics
+        #where_clause
+        {
+            fn members(&mut self) -> &mut GroupMembers {
+                &mut self.members
+            }
         }
 
         impl #impl_generics #struct_name #ty_generics
         #where_clause
         {
-            fn set_characteristic_module<M>(&mut self, module: &mut M) 
+            fn set_characteristic_module<M>(&mut self, module: &mut M) -> Result<(), ConnectionError>
             where
                 M: MetaSignals + UpdateReceivePorts + 'static,
             {
-                self.activity.connect_to_source(module.get_activity_port());
-                self.target_rating.connect_to_source(module.get_target_rating_port());
+                if !self.members.is_empty() {
+                    return Err(ConnectionError::AlreadyConnected);
+                }
+                self.activity.connect_to_source(module.get_activity_port())?;
+                self.target_rating.connect_to_source(module.get_target_rating_port())?;
 
-                self.stimulation.connect_as_source(module.get_stimulation_port());
-                self.inhibition.connect_as_source(module.get_inhibition_port());
+                self.stimulation.connect_as_source(module.get_stimulation_port())?;
+                self.inhibition.connect_as_source(module.get_inhibition_port())
+            }
+
+            fn add_member<M>(&mut self, module: &mut M) -> Result<(), ConnectionError>
+            where
+                M: MetaSignals + 'static,
+            {
+                if self.activity.has_source() {
+                    return Err(ConnectionError::AlreadyConnected);
+                }
+                self.members.add(module)?;
+                self.stimulation.connect_as_source(module.get_stimulation_port())?;
+                self.inhibition.connect_as_source(module.get_inhibition_port())
             }
         }
     };
@@ -140,7 +499,7 @@ pub fn ports(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let generics = input.generics.clone();
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let fields = if let Fields::Named(fields_named) = input.fields.clone() {
+    let mut fields = if let Fields::Named(fields_named) = input.fields.clone() {
         fields_named.named
     } else {
         return TokenStream::from(quote! {
@@ -149,20 +508,37 @@ pub fn ports(_attr: TokenStream, item: TokenStream) -> TokenStream {
     };
 
     let mut receive_port_updates = Vec::new();
+    let mut sync_groups = Vec::new();
+    let mut port_watches = Vec::new();
+    let mut port_swaps = Vec::new();
+    let mut port_links = Vec::new();
     for field in &fields {
         let field_name = field.ident.clone().unwrap();
 
-        if let Type::Path(type_path) = &field.ty {
-            if let Some(ident) = type_path.path.segments.last().map(|s| &s.ident) {
-                if ident == "ReceivePort" || ident == "ParameterPort" {
-                    receive_port_updates.push(quote! {
-                        self.#field_name.update();
-                    });
-                }
-            }
+        if is_port_field(field) {
+            sync_groups.push(quote! {
+                PortField::sync_groups(&self.#field_name, &mut sync_groups);
+            });
+            port_watches.push(quote! {
+                PortField::watch_ports(&self.#field_name, trigger);
+            });
+            receive_port_updates.push(quote! {
+                PortField::update_port(&mut self.#field_name);
+            });
+            port_swaps.push(quote! {
+                std::mem::swap(&mut self.#field_name, &mut other.#field_name);
+            });
+            port_links.push(quote! {
+                PortField::port_links(&self.#field_name, stringify!(#field_name), &mut links);
+            });
         }
     }   
 
+    // #[port] is only a marker for the macros and has to be removed from the struct
+    for field in fields.iter_mut() {
+        field.attrs.retain(|attr| !attr.path().is_ident("port"));
+    }
+
     let expanded = quote! {
         #(#attrs)*
         #[derive(Default)]
@@ -229,10 +605,45 @@ pub fn ports(_attr: TokenStream, item: TokenStream) -> TokenStream {
         #where_clause
         {
             fn update_all_ports(&mut self) {
+                let mut sync_groups = Vec::new();
+                #(#sync_groups)*
+                let _sync_guards = SyncPorts::read_all(&mut sync_groups);
                 #(#receive_port_updates)*
                 self.stimulation.update();
                 self.inhibition.update();
             }
+
+            fn watch_receive_ports(&self, trigger: &ChangeTrigger) {
+                #(#port_watches)*
+                trigger.watch(&self.stimulation);
+                trigger.watch(&self.inhibition);
+            }
+        }
+
+        impl #impl_generics PortLinks for #struct_name #ty_generics
+        #where_clause
+        {
+            fn port_links(&self) -> Vec<PortLink> {
+                let mut links = Vec::new();
+                #(#port_links)*
+                PortField::port_links(&self.activity, "activity", &mut links);
+                PortField::port_links(&self.target_rating, "target_rating", &mut links);
+                links.push(self.stimulation.meta_signal_link("stimulation"));
+                links.push(self.inhibition.meta_signal_link("inhibition"));
+                links
+            }
+        }
+
+        impl #impl_generics SwapPorts for #struct_name #ty_generics
+        #where_clause
+        {
+            fn swap_ports(&mut self, other: &mut Self) {
+                #(#port_swaps)*
+                std::mem::swap(&mut self.activity, &mut other.activity);
+                std::mem::swap(&mut self.target_rating, &mut other.target_rating);
+                std::mem::swap(&mut self.stimulation, &mut other.stimulation);
+                std::mem::swap(&mut self.inhibition, &mut other.inhibition);
+            }
         }
 
     };
@@ -243,7 +654,7 @@ pub fn ports(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// Automatically spawns all BehaviorModules, MaximumFusions, and BehaviorGroups defined in a function
 /// 
 /// # Example
-/// ```rust	ignore
+/// ```rust ignore
 /// use ib2c::prelude::*;
 /// 
 /// struct MyGroup { 
@@ -272,7 +683,7 @@ pub fn spawn(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 $module_type:ty, 
                 $name:expr
             ) => {
-                SpawnModule!($module_type, $name, cycle_time)
+                SpawnModule!($module_type, $name, <$module_type as Module>::CYCLE_TIME.unwrap_or(cycle_time))
             };
             (
                 $module_type:ty, 
@@ -314,11 +725,24 @@ pub fn spawn(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 inputs: [
                     $( $module:ident . $port:ident ),*$(,)?
                 ]
+            ) => {
+                SpawnFusion!($module_type, $name, cycle_time: cycle_time, inputs: [$( $module . $port ),*])
+            };
+            (
+                $module_type:ident,
+                $name:expr,
+                cycle_time: $cycle_time:expr,
+                inputs: [
+                    $( $module:ident . $port:ident ),*$(,)?
+                ]
             ) => {
                 {
-                    let mut fusion_module = $module_type::with_name($name, cycle_time, parent);
+                    let mut fusion_module = $module_type::with_name($name, $cycle_time, parent);
                     $(
-                        fusion_module.connect_module(&*$module, &$module.$port);
+                        fusion_module.connect_module(&*$module, &$module.$port)
+                            .unwrap_or_else(|error| panic!("Failed to connect {}.{} to fusion module {}: {}", stringify!($module), stringify!($port), $name, error));
+                        fusion_module.check_input_cycle_time($module.cycle_time())
+                            .unwrap_or_else(|error| panic!("Fusion module {} can not follow {}.{}: {}", $name, stringify!($module), stringify!($port), error));
                     )*
                     fusion_module
                 }
@@ -337,14 +761,14 @@ pub fn spawn(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 continue;
             };
 
-            if let Some(local_init) = &local.init {
-                if let syn::Expr::Macro(mac) = &*local_init.expr {
-                    let mac_path = &mac.mac.path;
-                    if mac_path.is_ident("SpawnModule") || mac_path.is_ident("SpawnFusion") {
-                        // Parse the quoted statement into a Stmt
-                        let stmt: Stmt = syn::parse_quote! { #var.spawn(); };
-                        spawn_stmts.push(stmt);
-                    }
+            if let Some(local_init) = &local.init
+                && let syn::Expr::Macro(mac) = &*local_init.expr
+            {
+                let mac_path = &mac.mac.path;
+                if mac_path.is_ident("SpawnModule") || mac_path.is_ident("SpawnFusion") {
+                    // Parse the quoted statement into a Stmt
+                    let stmt: Stmt = syn::parse_quote! { #var.spawn(); };
+                    spawn_stmts.push(stmt);
                 }
             }
         }
@@ -357,18 +781,17 @@ pub fn spawn(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 continue;
             };
 
-            if let Some(local_init) = &local.init {
-                if let syn::Expr::Call(call) = &*local_init.expr {
-                    if let syn::Expr::Path(path) = &*call.func {
-                        for seg in &path.path.segments {
-                            let type_ident = &seg.ident;
-                            if type_ident == "BehaviorModule" || type_ident == "MaximumFusion" {
-                                // Parse the quoted statement into a Stmt
-                                let stmt: Stmt = syn::parse_quote! { #var.spawn(); };
-                                spawn_stmts.push(stmt);
-                                break;
-                            }
-                        }
+            if let Some(local_init) = &local.init
+                && let syn::Expr::Call(call) = &*local_init.expr
+                && let syn::Expr::Path(path) = &*call.func
+            {
+                for seg in &path.path.segments {
+                    let type_ident = &seg.ident;
+                    if type_ident == "BehaviorModule" || type_ident == "MaximumFusion" {
+                        // Parse the quoted statement into a Stmt
+                        let stmt: Stmt = syn::parse_quote! { #var.spawn(); };
+                        spawn_stmts.push(stmt);
+                        break;
                     }
                 }
             }
@@ -379,4 +802,152 @@ pub fn spawn(_attr: TokenStream, item: TokenStream) -> TokenStream {
     input.block.stmts.extend(spawn_stmts);
 
     TokenStream::from(quote! { #input })
-}
\ No newline at end of file
+}
+
+/// Declares the modules, subgroups and fusion modules of a group together with their connections.
+/// All declared modules and fusion modules are registered with `Parent::spawn` and started once `Group::init` returns.
+/// Like the `Spawn*!` macros it is used in `Group::init` and expects `cycle_time` and `parent` in scope.
+/// 
+/// Connections are type checked at compile time. Failing connections panic with the names of both ports.
+/// 
+/// # Example
+/// ```rust ignore
+/// fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+///     network! {
+///         // variable: Type("Name") or Type("Name", cycle_time), optionally followed by settings
+///         module break_on_obstacle: BreakOnObstacle("BreakOnObstacle");
+///         module constant_velocity: ConstantVelocity("ConstantVelocity").set_control_mode(ControlMode::WhenActive);
+///         group steering: Steering("Steering");
+///         fusion velocity_fusion: MaximumFusion("VelocityFusion") <- [break_on_obstacle.out_velocity, constant_velocity.out_velocity];
+///
+///         // data connections from source to target
+///         self.in_front_distance_sensor -> break_on_obstacle.in_distance;
+///         velocity_fusion.output -> self.out_velocity;
+///         // connects the activity of the first module to the stimulation or inhibition of the second
+///         break_on_obstacle inhibits steering;
+///         characteristic velocity_fusion;
+///     }
+/// }
+/// ```
+/// Instead of a characteristic module, groups with several top-level behaviors declare them as members with `member steering;`.
+/// The activity of the group is then aggregated from all members, see `Group::ACTIVITY_AGGREGATION`.
+#[proc_macro]
+pub fn network(item: TokenStream) -> TokenStream {
+    let network = parse_macro_input!(item as network::Network);
+    match network.expand() {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Derives `PortSerialization` for structs and enums whose fields implement `PortSerialization`.
+///
+/// Structs are serialized as `PortData::Struct` with one entry per field. Unnamed fields are named by their index.
+/// Enums are serialized as `PortData::Enum` with the variant name and the serialized fields as payload.
+/// 
+/// # Example
+/// ```rust ignore
+/// #[derive(Clone, Default, PortSerialization)]
+/// pub struct Obstacle {
+///     pub distance: Distance,
+///     pub bearing: Radian,
+/// }
+/// ```
+#[proc_macro_derive(PortSerialization)]
+pub fn derive_port_serialization(item: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(item as DeriveInput);
+    let name = input.ident.clone();
+
+    let type_params: Vec<_> = input.generics.type_params().map(|param| param.ident.clone()).collect();
+    let where_clause = input.generics.make_where_clause();
+    for param in type_params {
+        where_clause.predicates.push(parse_quote! { #param: PortSerialization });
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, serialization) = serialize_fields(&data.fields);
+            quote! {
+                let #name #pattern = self;
+                #serialization
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                let (pattern, serialization) = serialize_fields(&variant.fields);
+                let payload = match &variant.fields {
+                    Fields::Unit => quote! { None },
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! { Some(Box::new(__field_0.serialize_port_data())) },
+                    _ => quote! { Some(Box::new(#serialization)) },
+                };
+                quote! {
+                    #name::#variant_name #pattern => PortData::Enum {
+                        variant: stringify!(#variant_name).to_string(),
+                        payload: #payload,
+                    },
+                }
+            });
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return TokenStream::from(quote! {
+                compile_error!("PortSerialization can not be derived for unions.");
+            });
+        }
+    };
+
+    TokenStream::from(quote! {
+        impl #impl_generics PortSerialization for #name #ty_generics
+        #where_clause
+        {
+            fn serialize_port_data(&self) -> PortData {
+                #body
+            }
+        }
+    })
+}
+
+/// Creates a destructuring pattern for the given fields and an expression serializing them into a `PortData::Struct`.
+fn serialize_fields(fields: &Fields) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    match fields {
+        Fields::Named(fields) => {
+            let names: Vec<_> = fields.named.iter().map(|field| field.ident.clone().unwrap()).collect();
+            (
+                quote! { { #(#names),* } },
+                quote! {
+                    PortData::Struct {
+                        fields: vec![
+                            #( (stringify!(#names).to_string(), #names.serialize_port_data()), )*
+                        ],
+                    }
+                },
+            )
+        }
+        Fields::Unnamed(fields) => {
+            let bindings: Vec<_> = (0..fields.unnamed.len())
+                .map(|index| quote::format_ident!("__field_{}", index))
+                .collect();
+            let indices = (0..fields.unnamed.len()).map(|index| index.to_string());
+            (
+                quote! { ( #(#bindings),* ) },
+                quote! {
+                    PortData::Struct {
+                        fields: vec![
+                            #( (#indices.to_string(), #bindings.serialize_port_data()), )*
+                        ],
+                    }
+                },
+            )
+        }
+        Fields::Unit => (
+            quote! {},
+            quote! { PortData::Struct { fields: Vec::new() } },
+        ),
+    }
+}
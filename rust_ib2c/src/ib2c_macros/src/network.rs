@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, bracketed, parenthesized, parse::{Parse, ParseStream}, punctuated::Punctuated, token::{Comma, Paren}, Expr, Ident, Member, PathArguments, Token, Type};
+
+/// Kind of a node declared in a `network!`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    Module,
+    Group,
+    Fusion,
+}
+
+/// `module name: Type("Name", cycle_time).setting(...);`
+struct Node {
+    kind: NodeKind,
+    variable: Ident,
+    ty: Type,
+    arguments: Punctuated<Expr, Comma>,
+    settings: Vec<(Ident, Punctuated<Expr, Comma>)>,
+    /// Output ports connected to a fusion module, given as `module.port`.
+    inputs: Vec<(Ident, Ident)>,
+}
+
+enum Statement {
+    Node(Node),
+    /// `source -> target;`
+    Connection(Expr, Expr),
+    /// `source stimulates target;` or `source inhibits target;`
+    MetaSignal {
+        source: Ident,
+        target: Ident,
+        port: Ident,
+    },
+    /// `characteristic name;`
+    Characteristic(Ident),
+    /// `member name;`
+    Member(Ident),
+}
+
+pub struct Network {
+    statements: Vec<Statement>,
+}
+
+impl Parse for Network {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut statements = Vec::new();
+        while !input.is_empty() {
+            statements.push(parse_statement(input)?);
+            input.parse::<Token![;]>()?;
+        }
+        Ok(Self { statements })
+    }
+}
+
+fn parse_statement(input: ParseStream) -> syn::Result<Statement> {
+    if input.peek(Ident) && input.peek2(Ident) {
+        let keyword: Ident = input.parse()?;
+        let kind = match keyword.to_string().as_str() {
+            "module" => Some(NodeKind::Module),
+            "group" => Some(NodeKind::Group),
+            "fusion" => Some(NodeKind::Fusion),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            return parse_node(kind, input).map(Statement::Node);
+        }
+        if keyword == "characteristic" {
+            return Ok(Statement::Characteristic(input.parse()?));
+        }
+        if keyword == "member" {
+            return Ok(Statement::Member(input.parse()?));
+        }
+        let relation: Ident = input.parse()?;
+        let port = match relation.to_string().as_str() {
+            "stimulates" => Ident::new("stimulation", relation.span()),
+            "inhibits" => Ident::new("inhibition", relation.span()),
+            _ => return Err(syn::Error::new(relation.span(), "expected `stimulates` or `inhibits`")),
+        };
+        return Ok(Statement::MetaSignal { source: keyword, target: input.parse()?, port });
+    }
+    let source = parse_port(input)?;
+    input.parse::<Token![->]>()?;
+    let target = parse_port(input)?;
+    Ok(Statement::Connection(source, target))
+}
+
+/// Parses a port like `self.in_distance` or `module.in_values[0]` up to the next `->` or `;`.
+/// Expressions would take the `-` of `->` as a subtraction.
+fn parse_port(input: ParseStream) -> syn::Result<Expr> {
+    let mut tokens = TokenStream::new();
+    while !input.is_empty() && !input.peek(Token![->]) && !input.peek(Token![;]) {
+        tokens.extend([input.parse::<TokenTree>()?]);
+    }
+    if tokens.is_empty() {
+        return Err(input.error("expected a port like `module.out_velocity`"));
+    }
+    syn::parse2(tokens)
+}
+
+fn parse_node(kind: NodeKind, input: ParseStream) -> syn::Result<Node> {
+    let variable: Ident = input.parse()?;
+    input.parse::<Token![:]>()?;
+    // the type ends at the parenthesized arguments, `Type(...)` would be parsed as a function type otherwise
+    let ty_tokens: TokenStream = input.step(|cursor| {
+        let mut tokens = TokenStream::new();
+        let mut rest = *cursor;
+        while let Some((tree, next)) = rest.token_tree() {
+            if let TokenTree::Group(group) = &tree
+                && group.delimiter() == proc_macro2::Delimiter::Parenthesis
+            {
+                return Ok((tokens, rest));
+            }
+            tokens.extend([tree]);
+            rest = next;
+        }
+        Err(cursor.error("expected the name of the module in parentheses, e.g. `BreakOnObstacle(\"BreakOnObstacle\")`"))
+    })?;
+    let ty: Type = syn::parse2(ty_tokens)?;
+    let arguments = parse_arguments(input)?;
+    if arguments.is_empty() || arguments.len() > 2 {
+        return Err(syn::Error::new_spanned(&ty, "expected a name and an optional cycle time, e.g. `(\"Name\")` or `(\"Name\", cycle_time)`"));
+    }
+
+    let mut settings = Vec::new();
+    while input.peek(Token![.]) {
+        input.parse::<Token![.]>()?;
+        let setting: Ident = input.parse()?;
+        settings.push((setting, parse_arguments(input)?));
+    }
+
+    let mut inputs = Vec::new();
+    if input.peek(Token![<-]) {
+        let arrow = input.parse::<Token![<-]>()?;
+        if kind != NodeKind::Fusion {
+            return Err(syn::Error::new_spanned(arrow, "only fusion modules have inputs"));
+        }
+        let content;
+        bracketed!(content in input);
+        for input in Punctuated::<Expr, Comma>::parse_terminated(&content)? {
+            inputs.push(parse_output_port(&input)?);
+        }
+    } else if kind == NodeKind::Fusion {
+        return Err(input.error("expected the inputs of the fusion module, e.g. `<- [module.out_velocity]`"));
+    }
+
+    Ok(Node { kind, variable, ty, arguments, settings, inputs })
+}
+
+fn parse_arguments(input: ParseStream) -> syn::Result<Punctuated<Expr, Comma>> {
+    if !input.peek(Paren) {
+        return Err(input.error("expected arguments in parentheses"));
+    }
+    let content;
+    parenthesized!(content in input);
+    Punctuated::parse_terminated(&content)
+}
+
+/// Splits an input of a fusion module like `module.out_velocity` into the module and the port.
+fn parse_output_port(expr: &Expr) -> syn::Result<(Ident, Ident)> {
+    if let Expr::Field(field) = expr
+        && let Expr::Path(path) = &*field.base
+        && let Some(module) = path.path.get_ident()
+        && let Member::Named(port) = &field.member
+    {
+        return Ok((module.clone(), port.clone()));
+    }
+    Err(syn::Error::new_spanned(expr, "expected an output port like `module.out_velocity`"))
+}
+
+/// Converts a type like `MaximumFusion<Velocity>` into a path usable in expressions, like `MaximumFusion::<Velocity>`.
+fn expression_path(ty: &Type) -> syn::Result<syn::Path> {
+    let Type::Path(type_path) = ty else {
+        return Err(syn::Error::new_spanned(ty, "expected the type of a fusion module like `MaximumFusion`"));
+    };
+    let mut path = type_path.path.clone();
+    for segment in path.segments.iter_mut() {
+        if let PathArguments::AngleBracketed(arguments) = &mut segment.arguments {
+            arguments.colon2_token = Some(Default::default());
+        }
+    }
+    Ok(path)
+}
+
+impl Network {
+    pub fn expand(self) -> syn::Result<TokenStream> {
+        let mut kinds = HashMap::new();
+        for statement in &self.statements {
+            if let Statement::Node(node) = statement
+                && kinds.insert(node.variable.to_string(), node.kind).is_some()
+            {
+                return Err(syn::Error::new_spanned(&node.variable, "declared more than once in this network"));
+            }
+        }
+        // modules and groups are passed as references to the wrapped module, fusion modules are not wrapped
+        let unwrapped = |module: &Ident| match kinds.get(&module.to_string()) {
+            Some(NodeKind::Fusion) => quote! { #module },
+            _ => quote! { *#module },
+        };
+
+        let nodes: Vec<&Node> = self.statements.iter()
+            .filter_map(|statement| match statement {
+                Statement::Node(node) => Some(node),
+                _ => None,
+            })
+            .collect();
+
+        let mut creations = Vec::new();
+        let mut spawns = Vec::new();
+        // fusion modules are created after all other nodes, so their inputs exist
+        for node in nodes.iter().filter(|node| node.kind != NodeKind::Fusion).chain(nodes.iter().filter(|node| node.kind == NodeKind::Fusion)) {
+            let Node { kind, variable, ty, arguments, settings, inputs } = node;
+            let name = &arguments[0];
+            let cycle_time = match (arguments.get(1), kind) {
+                (Some(cycle_time), _) => quote! { #cycle_time },
+                (None, NodeKind::Module) => quote! { <#ty as Module>::CYCLE_TIME.unwrap_or(cycle_time) },
+                (None, _) => quote! { cycle_time },
+            };
+            let creation = match kind {
+                NodeKind::Module => quote! { BehaviorModule::<#ty>::with_name(#name, #cycle_time, parent) },
+                NodeKind::Group => quote! { BehaviorGroup::<#ty>::with_name(#name, #cycle_time, parent) },
+                NodeKind::Fusion => {
+                    let path = expression_path(ty)?;
+                    quote! { #path::with_name(#name, #cycle_time, parent) }
+                }
+            };
+            let settings = settings.iter().map(|(setting, arguments)| quote! { #variable.#setting(#arguments); });
+            let inputs = inputs.iter().map(|(module, port)| {
+                let module_reference = unwrapped(module);
+                quote! {
+                    #variable.connect_module(&#module_reference, &#module.#port)
+                        .unwrap_or_else(|error| panic!("Failed to connect {}.{} to fusion module {}: {}", stringify!(#module), stringify!(#port), #name, error));
+                    #variable.check_input_cycle_time(#module.cycle_time())
+                        .unwrap_or_else(|error| panic!("Fusion module {} can not follow {}.{}: {}", #name, stringify!(#module), stringify!(#port), error));
+                }
+            });
+            creations.push(quote! {
+                #[allow(unused_mut, unused_variables)]
+                let mut #variable = #creation;
+                #(#settings)*
+                #(#inputs)*
+            });
+            if *kind != NodeKind::Group {
+                spawns.push(quote! { parent.spawn(#variable); });
+            }
+        }
+
+        let connections = self.statements.iter().filter_map(|statement| match statement {
+            Statement::Node(_) => None,
+            // spanned at the ports, so type mismatches are reported at the connection
+            Statement::Connection(source, target) => {
+                let source_reference = quote_spanned! {source.span()=> &#source };
+                Some(quote_spanned! {target.span()=>
+                    #target.connect_to_source(#source_reference)
+                        .unwrap_or_else(|error| panic!("Failed to connect {} to {}: {}", stringify!(#source), stringify!(#target), error));
+                })
+            }
+            Statement::MetaSignal { source, target, port } => Some(quote! {
+                #target.#port.connect_to_source(#source.get_activity_port())
+                    .unwrap_or_else(|error| panic!("Failed to connect the activity of {} to the {} of {}: {}", stringify!(#source), stringify!(#port), stringify!(#target), error));
+            }),
+            Statement::Characteristic(module) => {
+                let module_reference = unwrapped(module);
+                Some(quote! {
+                    self.set_characteristic_module(&mut #module_reference)
+                        .unwrap_or_else(|error| panic!("Failed to set {} as characteristic module: {}", stringify!(#module), error));
+                })
+            }
+            Statement::Member(module) => {
+                let module_reference = unwrapped(module);
+                Some(quote! {
+                    self.add_member(&mut #module_reference)
+                        .unwrap_or_else(|error| panic!("Failed to add {} as member of the group: {}", stringify!(#module), error));
+                })
+            }
+        });
+
+        Ok(quote! {
+            #(#creations)*
+            #(#connections)*
+            #(#spawns)*
+        })
+    }
+}
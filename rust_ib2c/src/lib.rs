@@ -10,22 +10,47 @@ pub mod group;
 pub mod fusion_module;
 /// Data structures and utilities for meta-signals.
 pub mod meta_signals;
+/// Error types and fault handling policies for modules.
+pub mod fault;
+/// Loading of parameter values from parameter files.
+pub mod parameters;
+/// Structure of behavior networks and their export as diagrams.
+pub mod network;
 /// TCP server for remote monitoring and control of modules.
 pub(crate) mod tcp_server;
 
+/// Polls the condition until it holds or a deadline of one second has passed.
+/// Returns whether the condition held, so tests do not depend on how fast the module threads are scheduled.
+#[cfg(test)]
+pub(crate) fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(1);
+    while !condition() {
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    true
+}
+
 /// Re-exports commonly used items for easier access.
 pub mod prelude {
-    pub use crate::traits::{Module, Group, MetaSignals, UpdateReceivePorts, PortSerialization, PortParsing};
-    pub use crate::port::{SendPort, ReceivePort, OutputPort, InputPort, ParameterPort};
-    pub use crate::behavior_module::BehaviorModule;
-    pub use crate::group::BehaviorGroup;
-    pub use crate::fusion_module::MaximumFusion;
-    pub use crate::meta_signals::MetaSignal;
+    pub use crate::traits::{Module, Group, MetaSignals, UpdateReceivePorts, PortSerialization, PortParsing, SwapPorts, PortField, PortLinks, Members, ModuleAttributes, Spawnable};
+    pub use crate::parameters::{ParameterLoading, ParameterValue, ParameterReader, ParseParameter, ParameterSet, ParameterError, ParameterFormat, ParameterDeclaration, OutOfRangeError, ParameterAccess, LoadableParameter, OpaqueParameter};
+    pub use crate::port::{SendPort, ReceivePort, OutputPort, InputPort, ParameterPort, ConnectPorts, ConnectionError, SyncPorts, ChangeTrigger, PortLink, port_vec};
+    pub use crate::network::{Network, NetworkIssue, DiagramFormat};
+    pub use crate::behavior_module::{BehaviorModule, ControlMode, ExecutionMode};
+    pub use crate::group::{BehaviorGroup, GroupMembers};
+    pub use crate::fusion_module::{MaximumFusion, CycleTimeError};
+    pub use crate::meta_signals::{MetaSignal, ActivationDynamics, ActivityAggregation};
+    pub use crate::fault::{ModuleError, FaultPolicy, RestartStrategy};
     pub use crate::tcp_server::Parent;
     pub use ib2c_macros::module;
     pub use ib2c_macros::group;
     pub use ib2c_macros::ports;
     pub use ib2c_macros::spawn;
-    pub use rust_ib2c_shared_data::PortData;
+    pub use ib2c_macros::network;
+    pub use ib2c_macros::PortSerialization;
+    pub use rust_ib2c_shared_data::{PortData, PortDirection, ParameterInfo};
     pub use crate::SpawnMainGroup;
 }
\ No newline at end of file
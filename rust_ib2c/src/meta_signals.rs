@@ -1,4 +1,4 @@
-use std::{fmt::Display, ops::{Add, AddAssign, Deref, Div, DivAssign, Mul, MulAssign, Sub, SubAssign}};
+use std::{fmt::Display, ops::{Add, AddAssign, Deref, Div, DivAssign, Mul, MulAssign, Sub, SubAssign}, time::Duration};
 
 /// A meta-signal representing a value between 0.0 and 1.0 inclusive.
 /// Supports arithmetic operations and comparisons.
@@ -292,7 +292,7 @@ impl Eq for MetaSignal {}
 
 impl PartialOrd for MetaSignal {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.value.partial_cmp(&other.value)
+        Some(self.cmp(other))
     }
 }
 
@@ -320,9 +320,99 @@ impl Display for MetaSignal {
     }
 }
 
+/// Dynamics applied to the activity of a [`BehaviorModule`][crate::behavior_module::BehaviorModule].
+/// Without dynamics the activity follows `min(stimulation, 1 - inhibition, target_rating)` instantly.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ActivationDynamics {
+    /// Activity follows its target value instantly.
+    #[default]
+    Instant,
+    /// Limits the change of activity per second when rising and falling.
+    /// Only the magnitude of a rate is used, a NaN rate does not limit the change.
+    RateLimit {
+        rise_rate: f32,
+        fall_rate: f32,
+    },
+    /// First-order low-pass filter with the given time constant.
+    LowPass {
+        time_constant: Duration,
+    },
+    /// Linear ramp taking `rise_time` to go from 0.0 to 1.0 and `fall_time` to go from 1.0 to 0.0.
+    RiseFall {
+        rise_time: Duration,
+        fall_time: Duration,
+    },
+}
+
+impl ActivationDynamics {
+    /// Computes the next activity from the previous activity, the target activity and the elapsed time.
+    pub fn apply(&self, previous: MetaSignal, target: MetaSignal, delta_time: Duration) -> MetaSignal {
+        let dt = delta_time.as_secs_f32();
+        match *self {
+            ActivationDynamics::Instant => target,
+            ActivationDynamics::RateLimit { rise_rate, fall_rate } => {
+                Self::ramp(previous, target, rise_rate * dt, fall_rate * dt)
+            }
+            ActivationDynamics::LowPass { time_constant } => {
+                if time_constant.is_zero() {
+                    return target;
+                }
+                let alpha = 1.0 - (-dt / time_constant.as_secs_f32()).exp();
+                MetaSignal::new(previous.value + alpha * (target.value - previous.value))
+            }
+            ActivationDynamics::RiseFall { rise_time, fall_time } => {
+                let max_rise = if rise_time.is_zero() { 1.0 } else { dt / rise_time.as_secs_f32() };
+                let max_fall = if fall_time.is_zero() { 1.0 } else { dt / fall_time.as_secs_f32() };
+                Self::ramp(previous, target, max_rise, max_fall)
+            }
+        }
+    }
+
+    fn ramp(previous: MetaSignal, target: MetaSignal, max_rise: f32, max_fall: f32) -> MetaSignal {
+        // clamp panics if the lower bound exceeds the upper bound or one of them is NaN
+        let limit = |max_change: f32| if max_change.is_nan() { f32::INFINITY } else { max_change.abs() };
+        let difference = target.value - previous.value;
+        MetaSignal::new(previous.value + difference.clamp(-limit(max_fall), limit(max_rise)))
+    }
+}
+
+/// Rule combining the activities and target ratings of the members of a group, see [`Group::ACTIVITY_AGGREGATION`][crate::traits::Group::ACTIVITY_AGGREGATION].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActivityAggregation {
+    /// Activity and target rating of the most active member. The first member wins if several are equally active.
+    #[default]
+    Maximum,
+    /// Sum of the activities, limited to HIGH. The target rating is the mean of the target ratings weighted by the activities.
+    Sum,
+}
+
+impl ActivityAggregation {
+    /// Combines `(activity, target_rating)` of all members. Returns LOW for both without members,
+    /// and a LOW target rating for [`ActivityAggregation::Sum`] if no member is active.
+    pub fn aggregate(&self, members: impl IntoIterator<Item = (MetaSignal, MetaSignal)>) -> (MetaSignal, MetaSignal) {
+        match self {
+            ActivityAggregation::Maximum => members.into_iter()
+                .reduce(|best, member| if member.0 > best.0 { member } else { best })
+                .unwrap_or((MetaSignal::LOW, MetaSignal::LOW)),
+            ActivityAggregation::Sum => {
+                let (activity, weighted_target_rating) = members.into_iter()
+                    .fold((0.0, 0.0), |(activity, weighted), (member_activity, target_rating)| {
+                        (activity + *member_activity, weighted + *member_activity * *target_rating)
+                    });
+                if activity == 0.0 {
+                    return (MetaSignal::LOW, MetaSignal::LOW);
+                }
+                (MetaSignal::new(activity), MetaSignal::new(weighted_target_rating / activity))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::MetaSignal;
+    use std::time::Duration;
+
+    use super::{ActivationDynamics, ActivityAggregation, MetaSignal};
     #[test]
     fn test_meta_signal() {
         let mut a = MetaSignal::new(0.5);
@@ -393,4 +483,50 @@ mod tests {
         assert!(0.4 != a);
         assert!(1.0 == a);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_activation_dynamics() {
+        let dt = Duration::from_millis(250);
+
+        let instant = ActivationDynamics::Instant;
+        assert_eq!(instant.apply(MetaSignal::LOW, MetaSignal::HIGH, dt), MetaSignal::HIGH);
+
+        let rate_limit = ActivationDynamics::RateLimit { rise_rate: 1.0, fall_rate: 2.0 };
+        assert_eq!(rate_limit.apply(MetaSignal::LOW, MetaSignal::HIGH, dt), 0.25);
+        assert_eq!(rate_limit.apply(MetaSignal::HIGH, MetaSignal::LOW, dt), 0.5);
+        assert_eq!(rate_limit.apply(MetaSignal::new(0.875), MetaSignal::HIGH, dt), MetaSignal::HIGH);
+
+        let invalid_rate_limit = ActivationDynamics::RateLimit { rise_rate: -1.0, fall_rate: f32::NAN };
+        assert_eq!(invalid_rate_limit.apply(MetaSignal::LOW, MetaSignal::HIGH, dt), 0.25);
+        assert_eq!(invalid_rate_limit.apply(MetaSignal::HIGH, MetaSignal::LOW, dt), MetaSignal::LOW);
+
+        let rise_fall = ActivationDynamics::RiseFall {
+            rise_time: Duration::from_secs(2),
+            fall_time: Duration::ZERO,
+        };
+        assert_eq!(rise_fall.apply(MetaSignal::LOW, MetaSignal::HIGH, dt), 0.125);
+        assert_eq!(rise_fall.apply(MetaSignal::HIGH, MetaSignal::LOW, dt), MetaSignal::LOW);
+
+        let low_pass = ActivationDynamics::LowPass { time_constant: Duration::from_millis(100) };
+        let mut activity = MetaSignal::LOW;
+        for _ in 0..10 {
+            activity = low_pass.apply(activity, MetaSignal::HIGH, Duration::from_millis(10));
+        }
+        assert!((*activity - (1.0 - (-1.0f32).exp())).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_activity_aggregation() {
+        let members = [
+            (MetaSignal::new(0.25), MetaSignal::HIGH),
+            (MetaSignal::new(0.75), MetaSignal::new(0.5)),
+            (MetaSignal::new(0.75), MetaSignal::LOW),
+        ];
+        assert_eq!(ActivityAggregation::Maximum.aggregate(members), (MetaSignal::new(0.75), MetaSignal::new(0.5)));
+        let (activity, target_rating) = ActivityAggregation::Sum.aggregate(members);
+        assert_eq!(activity, MetaSignal::HIGH);
+        assert!((*target_rating - (0.25 + 0.375) / 1.75).abs() < 1e-6);
+        assert_eq!(ActivityAggregation::Sum.aggregate([(MetaSignal::LOW, MetaSignal::HIGH)]), (MetaSignal::LOW, MetaSignal::LOW));
+        assert_eq!(ActivityAggregation::Maximum.aggregate([]), (MetaSignal::LOW, MetaSignal::LOW));
+    }
+}
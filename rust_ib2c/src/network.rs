@@ -0,0 +1,675 @@
+use std::{collections::HashMap, fmt::{Display, Write}, path::Path, sync::Mutex};
+
+use rust_ib2c_shared_data::PortDirection;
+
+use crate::{fusion_module::{ACTIVITY_INPUTS, DATA_INPUTS, TARGET_RATING_INPUTS}, meta_signals::MetaSignal, port::PortLink};
+
+/// Kind of a node of a behavior network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeKind {
+    Module,
+    Fusion,
+    Group,
+}
+
+/// Kind of a connection between two nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    Data,
+    /// Connection to the stimulation port of a module.
+    Stimulation,
+    /// Connection to the inhibition port of a module.
+    Inhibition,
+}
+
+/// Port of a node and the port it is connected to, identified by [`PortLink::id`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkPort {
+    pub name: String,
+    pub direction: PortDirection,
+    pub id: usize,
+    pub source: Option<usize>,
+    /// Value of stimulation and inhibition ports when the network was created.
+    pub meta_signal: Option<MetaSignal>,
+}
+
+/// Module, fusion module or group of a behavior network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkNode {
+    pub path: String,
+    pub kind: NodeKind,
+    pub ports: Vec<NetworkPort>,
+}
+
+impl NetworkNode {
+    /// Name of the node, the last element of its path.
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Connection from a port of one node to a port of another node. Nodes are indices into [`Network::nodes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkEdge {
+    pub source: usize,
+    pub source_port: String,
+    pub target: usize,
+    pub target_port: String,
+    pub kind: EdgeKind,
+}
+
+/// Problem in the structure of a behavior network found by [`Network::check`]. Nodes are given by their path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkIssue {
+    /// A receive port of a module or subgroup is not connected to a source, so it never receives data.
+    /// Stimulation and inhibition are optional and not reported.
+    UnconnectedInput {
+        node: String,
+        port: String,
+    },
+    /// No port of the network reads an output of a module, fusion module or subgroup.
+    /// Activity and target rating are not reported.
+    UnreadOutput {
+        node: String,
+        port: String,
+    },
+    /// A fusion module with less than two inputs has nothing to select from.
+    SingleInputFusion {
+        fusion: String,
+        inputs: usize,
+    },
+    /// Warning that the inhibition of a module is connected to a port outside of the network that was HIGH
+    /// when the network was captured, e.g. a `SendPort` created in [`Group::init`][crate::traits::Group::init]
+    /// that keeps the activity of the module LOW.
+    /// The value is a snapshot: a port driven by the application may release the inhibition later,
+    /// and a HIGH value sent after the snapshot is missed. Inhibitions passed in through groups are not reported.
+    InhibitedAtSnapshot {
+        module: String,
+    },
+    /// Modules and fusion modules that inhibit each other in a loop, directly or through fusion modules.
+    InhibitionCycle {
+        nodes: Vec<String>,
+    },
+}
+
+impl Display for NetworkIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkIssue::UnconnectedInput { node, port } => write!(f, "{}.{} is not connected to a source", node, port),
+            NetworkIssue::UnreadOutput { node, port } => write!(f, "{}.{} is not read by any port", node, port),
+            NetworkIssue::SingleInputFusion { fusion, inputs: 0 } => write!(f, "fusion module {} has no inputs", fusion),
+            NetworkIssue::SingleInputFusion { fusion, inputs } => write!(f, "fusion module {} has only {} input", fusion, inputs),
+            NetworkIssue::InhibitedAtSnapshot { module } => write!(f, "{} was inhibited from outside of the network when the network was captured", module),
+            NetworkIssue::InhibitionCycle { nodes } => write!(f, "cyclic inhibition between {}", nodes.join(", ")),
+        }
+    }
+}
+
+/// File formats of network diagrams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    Dot,
+    Mermaid,
+}
+
+impl DiagramFormat {
+    /// Determines the format from the file extension (`.dot`, `.gv`, `.mmd` or `.mermaid`).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "dot" | "gv" => Some(DiagramFormat::Dot),
+            "mmd" | "mermaid" => Some(DiagramFormat::Mermaid),
+            _ => None,
+        }
+    }
+}
+
+/// Returns true for the ports added to every module and group by the `#[ports]` macro.
+fn is_meta_signal_port(name: &str) -> bool {
+    matches!(name, "activity" | "target_rating" | "stimulation" | "inhibition")
+}
+
+/// Returns true for the activity and target rating inputs of a [`MaximumFusion`][crate::fusion_module::MaximumFusion].
+fn is_fusion_meta_signal_input(name: &str) -> bool {
+    name.starts_with(ACTIVITY_INPUTS) || name.starts_with(TARGET_RATING_INPUTS)
+}
+
+/// Structure of a group: its modules, fusion modules and subgroups and the connections between them.
+///
+/// The meta signal ports of groups only pass signals through to their characteristic module,
+/// so connections are followed through them and drawn between the modules.
+/// Data ports of groups are kept as nodes of their own, as the interface of the group.
+/// The activity and target rating inputs of fusion modules are part of the fusion and are not listed as edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Network {
+    pub nodes: Vec<NetworkNode>,
+    pub edges: Vec<NetworkEdge>,
+    /// Node and port index of each port, by [`NetworkPort::id`].
+    ports: HashMap<usize, (usize, usize)>,
+}
+
+impl Network {
+    fn new(nodes: Vec<NetworkNode>) -> Self {
+        let ports = nodes.iter().enumerate()
+            .flat_map(|(node, ports)| ports.ports.iter().enumerate().map(move |(index, port)| (port.id, (node, index))))
+            .collect();
+        let mut network = Self { nodes, edges: Vec::new(), ports };
+
+        for (target, node) in network.nodes.iter().enumerate() {
+            for port in &node.ports {
+                let passthrough = match node.kind {
+                    NodeKind::Group => is_meta_signal_port(&port.name),
+                    NodeKind::Fusion => is_fusion_meta_signal_input(&port.name),
+                    NodeKind::Module => false,
+                };
+                if passthrough {
+                    continue;
+                }
+                let Some((source, source_port)) = network.resolve_source(port) else {
+                    continue;
+                };
+                let kind = match (node.kind, port.name.as_str()) {
+                    (NodeKind::Group, _) => EdgeKind::Data,
+                    (_, "stimulation") => EdgeKind::Stimulation,
+                    (_, "inhibition") => EdgeKind::Inhibition,
+                    _ => EdgeKind::Data,
+                };
+                network.edges.push(NetworkEdge {
+                    source,
+                    source_port: network.nodes[source].ports[source_port].name.clone(),
+                    target,
+                    target_port: port.name.clone(),
+                    kind,
+                });
+            }
+        }
+        network
+    }
+
+    /// Node and port index of the source of a port, following the meta signal ports of groups.
+    /// `None` if the source is not part of the network.
+    fn resolve_source(&self, port: &NetworkPort) -> Option<(usize, usize)> {
+        let mut source = port.source;
+        while let Some(&(node, index)) = self.ports.get(&source?) {
+            let source_port = &self.nodes[node].ports[index];
+            if self.nodes[node].kind == NodeKind::Group && is_meta_signal_port(&source_port.name) {
+                source = source_port.source;
+                continue;
+            }
+            return Some((node, index));
+        }
+        None
+    }
+
+    /// Checks the structure of the network and returns all issues found.
+    ///
+    /// The outermost group is connected by the application, so only its modules and subgroups are checked.
+    /// Ports read or connected outside of the network, e.g. by the application, are not known to the check.
+    /// ```rust ignore
+    /// let control_system = SpawnMainGroup!(ControlSystem, "ControlSystem", Duration::from_millis(10));
+    /// assert_eq!(control_system.network().check(), []);
+    /// ```
+    pub fn check(&self) -> Vec<NetworkIssue> {
+        let mut issues = Vec::new();
+        let read_ports: Vec<usize> = self.nodes.iter()
+            .flat_map(|node| node.ports.iter().filter_map(|port| port.source))
+            .collect();
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            if self.parent_group(index).is_none() {
+                continue;
+            }
+            for port in node.ports.iter().filter(|port| !is_meta_signal_port(&port.name)) {
+                let issue = match port.direction {
+                    PortDirection::Input if port.source.is_none() => NetworkIssue::UnconnectedInput { node: node.path.clone(), port: port.name.clone() },
+                    PortDirection::Output if !read_ports.contains(&port.id) => NetworkIssue::UnreadOutput { node: node.path.clone(), port: port.name.clone() },
+                    _ => continue,
+                };
+                issues.push(issue);
+            }
+        }
+
+        for node in self.nodes.iter().filter(|node| node.kind == NodeKind::Fusion) {
+            let inputs = node.ports.iter().filter(|port| port.name.starts_with(DATA_INPUTS)).count();
+            if inputs < 2 {
+                issues.push(NetworkIssue::SingleInputFusion { fusion: node.path.clone(), inputs });
+            }
+        }
+
+        for node in self.nodes.iter().filter(|node| node.kind == NodeKind::Module) {
+            if let Some(inhibition) = node.ports.iter().find(|port| port.name == "inhibition")
+                && inhibition.source.is_some_and(|source| !self.ports.contains_key(&source))
+                && inhibition.meta_signal == Some(MetaSignal::HIGH)
+            {
+                issues.push(NetworkIssue::InhibitedAtSnapshot { module: node.path.clone() });
+            }
+        }
+
+        issues.extend(self.inhibition_cycles().into_iter()
+            .map(|cycle| NetworkIssue::InhibitionCycle { nodes: cycle.into_iter().map(|node| self.nodes[node].path.clone()).collect() }));
+        issues
+    }
+
+    /// Groups of nodes inhibiting each other in a loop. The activity of a fusion module depends on the activity of its inputs,
+    /// so inhibition loops through fusion modules are found as well.
+    fn inhibition_cycles(&self) -> Vec<Vec<usize>> {
+        let mut successors = vec![Vec::new(); self.nodes.len()];
+        for edge in self.edges.iter().filter(|edge| edge.kind == EdgeKind::Inhibition) {
+            successors[edge.source].push(edge.target);
+        }
+        for (fusion, node) in self.nodes.iter().enumerate().filter(|(_, node)| node.kind == NodeKind::Fusion) {
+            for port in node.ports.iter().filter(|port| port.name.starts_with(ACTIVITY_INPUTS)) {
+                if let Some((input, _)) = self.resolve_source(port) {
+                    successors[input].push(fusion);
+                }
+            }
+        }
+
+        let reachable: Vec<Vec<bool>> = (0..self.nodes.len())
+            .map(|start| {
+                let mut reached = vec![false; self.nodes.len()];
+                let mut stack = successors[start].clone();
+                while let Some(node) = stack.pop() {
+                    if !std::mem::replace(&mut reached[node], true) {
+                        stack.extend(&successors[node]);
+                    }
+                }
+                reached
+            })
+            .collect();
+
+        let mut cycles: Vec<Vec<usize>> = Vec::new();
+        for node in (0..self.nodes.len()).filter(|&node| reachable[node][node]) {
+            if cycles.iter().any(|cycle| cycle.contains(&node)) {
+                continue;
+            }
+            cycles.push((0..self.nodes.len()).filter(|&other| reachable[node][other] && reachable[other][node]).collect());
+        }
+        cycles
+    }
+
+    /// Index of the group containing the node, `None` for the outermost group.
+    fn parent_group(&self, node: usize) -> Option<usize> {
+        let mut path = self.nodes[node].path.as_str();
+        while let Some((parent, _)) = path.rsplit_once('/') {
+            if let Some(index) = self.nodes.iter().position(|node| node.kind == NodeKind::Group && node.path == parent) {
+                return Some(index);
+            }
+            path = parent;
+        }
+        None
+    }
+
+    /// Ports of a group drawn as nodes inside of the group.
+    fn interface_ports(&self, group: usize) -> impl Iterator<Item = (usize, &NetworkPort)> {
+        self.nodes[group].ports.iter().enumerate()
+            .filter(|(_, port)| !is_meta_signal_port(&port.name))
+    }
+
+    /// Identifier of the drawn node of an edge end: the node itself, or the port for ports of groups.
+    fn endpoint_id(&self, node: usize, port: &str) -> String {
+        if self.nodes[node].kind == NodeKind::Group
+            && let Some(index) = self.nodes[node].ports.iter().position(|other| other.name == port)
+        {
+            return format!("n{}p{}", node, index);
+        }
+        format!("n{}", node)
+    }
+
+    /// Label of a data edge. Ports of groups are nodes themselves and the inputs of fusion modules are numbered internally,
+    /// so only the ports of modules and the output of fusion modules are named.
+    fn data_label(&self, edge: &NetworkEdge) -> String {
+        let mut label = Vec::new();
+        if self.nodes[edge.source].kind != NodeKind::Group {
+            label.push(edge.source_port.as_str());
+        }
+        if self.nodes[edge.target].kind == NodeKind::Module {
+            label.push(edge.target_port.as_str());
+        }
+        label.join(" → ")
+    }
+
+    /// Children of each group and the nodes outside of any group, in the order they were registered.
+    fn hierarchy(&self) -> (HashMap<usize, Vec<usize>>, Vec<usize>) {
+        let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut roots = Vec::new();
+        for node in 0..self.nodes.len() {
+            match self.parent_group(node) {
+                Some(group) => children.entry(group).or_default().push(node),
+                None => roots.push(node),
+            }
+        }
+        (children, roots)
+    }
+
+    /// Renders the network as a Graphviz DOT graph.
+    ///
+    /// Groups are clusters, modules boxes and fusion modules hexagons.
+    /// Data flows from left to right, stimulation and inhibition are dashed edges from the bottom of the source
+    /// to the top of the target module, ending with an arrow for stimulation and a bar for inhibition.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph network {\n    rankdir=LR;\n    node [fontname=\"Helvetica\"];\n    edge [fontname=\"Helvetica\", fontsize=10];\n");
+        let (children, roots) = self.hierarchy();
+        for node in roots {
+            self.write_dot_node(&mut dot, node, &children, 1);
+        }
+        for edge in &self.edges {
+            let source = self.endpoint_id(edge.source, &edge.source_port);
+            let target = self.endpoint_id(edge.target, &edge.target_port);
+            let attributes = match edge.kind {
+                EdgeKind::Data => match self.data_label(edge) {
+                    label if label.is_empty() => String::new(),
+                    label => format!(" [label=\"{}\"]", escape_dot(&label)),
+                },
+                EdgeKind::Stimulation => " [style=dashed, arrowhead=normal, tailport=s, headport=n, color=darkgreen]".to_string(),
+                EdgeKind::Inhibition => " [style=dashed, arrowhead=tee, tailport=s, headport=n, color=red]".to_string(),
+            };
+            let _ = writeln!(dot, "    {} -> {}{};", source, target, attributes);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot_node(&self, dot: &mut String, node: usize, children: &HashMap<usize, Vec<usize>>, depth: usize) {
+        let indent = "    ".repeat(depth);
+        let name = escape_dot(self.nodes[node].name());
+        match self.nodes[node].kind {
+            NodeKind::Module => {
+                let _ = writeln!(dot, "{}n{} [label=\"{}\", shape=box];", indent, node, name);
+            }
+            NodeKind::Fusion => {
+                let _ = writeln!(dot, "{}n{} [label=\"{}\\n(max)\", shape=hexagon];", indent, node, name);
+            }
+            NodeKind::Group => {
+                let _ = writeln!(dot, "{}subgraph cluster_n{} {{", indent, node);
+                let _ = writeln!(dot, "{}    label=\"{}\";\n{}    style=\"rounded,dashed\";", indent, name, indent);
+                for (index, port) in self.interface_ports(node) {
+                    let shape = if port.direction == PortDirection::Output { "rarrow" } else { "larrow" };
+                    let _ = writeln!(dot, "{}    n{}p{} [label=\"{}\", shape={}];", indent, node, index, escape_dot(&port.name), shape);
+                }
+                for &child in children.get(&node).into_iter().flatten() {
+                    self.write_dot_node(dot, child, children, depth + 1);
+                }
+                let _ = writeln!(dot, "{}}}", indent);
+            }
+        }
+    }
+
+    /// Renders the network as a Mermaid flowchart, with the same conventions as [`Network::to_dot`].
+    /// Inhibition edges end with a cross, as Mermaid has no bar arrowhead.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+        let (children, roots) = self.hierarchy();
+        for node in roots {
+            self.write_mermaid_node(&mut mermaid, node, &children, 1);
+        }
+        for edge in &self.edges {
+            let source = self.endpoint_id(edge.source, &edge.source_port);
+            let target = self.endpoint_id(edge.target, &edge.target_port);
+            let _ = match edge.kind {
+                EdgeKind::Data => match self.data_label(edge) {
+                    label if label.is_empty() => writeln!(mermaid, "    {} --> {}", source, target),
+                    label => writeln!(mermaid, "    {} -->|\"{}\"| {}", source, escape_mermaid(&label), target),
+                },
+                EdgeKind::Stimulation => writeln!(mermaid, "    {} -.->|stimulation| {}", source, target),
+                EdgeKind::Inhibition => writeln!(mermaid, "    {} -.-x|inhibition| {}", source, target),
+            };
+        }
+        mermaid
+    }
+
+    fn write_mermaid_node(&self, mermaid: &mut String, node: usize, children: &HashMap<usize, Vec<usize>>, depth: usize) {
+        let indent = "    ".repeat(depth);
+        let name = escape_mermaid(self.nodes[node].name());
+        match self.nodes[node].kind {
+            NodeKind::Module => {
+                let _ = writeln!(mermaid, "{}n{}[\"{}\"]", indent, node, name);
+            }
+            NodeKind::Fusion => {
+                let _ = writeln!(mermaid, "{}n{}{{{{\"{}<br>(max)\"}}}}", indent, node, name);
+            }
+            NodeKind::Group => {
+                let _ = writeln!(mermaid, "{}subgraph n{} [\"{}\"]", indent, node, name);
+                for (index, port) in self.interface_ports(node) {
+                    let _ = writeln!(mermaid, "{}    n{}p{}>\"{}\"]", indent, node, index, escape_mermaid(&port.name));
+                }
+                for &child in children.get(&node).into_iter().flatten() {
+                    self.write_mermaid_node(mermaid, child, children, depth + 1);
+                }
+                let _ = writeln!(mermaid, "{}end", indent);
+            }
+        }
+    }
+
+    /// Renders the network in the given format.
+    pub fn render(&self, format: DiagramFormat) -> String {
+        match format {
+            DiagramFormat::Dot => self.to_dot(),
+            DiagramFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Writes the network to a file, the format is determined by the file extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let format = DiagramFormat::from_path(path).ok_or_else(|| std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "unsupported format, expected a .dot, .gv, .mmd or .mermaid file",
+        ))?;
+        std::fs::write(path, self.render(format))
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+/// Ports of all spawned modules and initialized groups of a system, shared by all [`Parent`][crate::tcp_server::Parent]s of the system.
+#[derive(Default)]
+pub(crate) struct NetworkRegistry {
+    nodes: Mutex<Vec<(String, NodeKind, Vec<PortLink>)>>,
+}
+
+impl NetworkRegistry {
+    /// Registers the ports of a module, fusion module or group. Replaces the node that was registered with the same path before.
+    pub(crate) fn register(&self, path: &str, kind: NodeKind, ports: Vec<PortLink>) {
+        let mut nodes = self.nodes.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        nodes.retain(|(registered_path, _, _)| registered_path != path);
+        nodes.push((path.to_string(), kind, ports));
+    }
+
+    /// Returns the network of the group with the given path, with the current connections of all ports.
+    pub(crate) fn network(&self, group_path: &str) -> Network {
+        let prefix = format!("{}/", group_path);
+        let nodes = self.nodes.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).iter()
+            .filter(|(path, _, _)| path == group_path || path.starts_with(&prefix))
+            .map(|(path, kind, ports)| NetworkNode {
+                path: path.clone(),
+                kind: *kind,
+                ports: ports.iter()
+                    .map(|port| NetworkPort {
+                        name: port.name.clone(),
+                        direction: port.direction,
+                        id: port.id(),
+                        source: port.source_id(),
+                        meta_signal: port.meta_signal(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Network::new(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::prelude::*;
+
+    #[module]
+    struct Producer {
+        pub out_value: SendPort<u32>,
+    }
+
+    impl Module for Producer {
+        fn transfere(&mut self) {
+            self.out_value.send(1);
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[module]
+    struct Filter {
+        pub in_value: ReceivePort<u32>,
+        pub out_value: SendPort<u32>,
+    }
+
+    impl Module for Filter {
+        fn transfere(&mut self) {
+            if let Some(value) = self.in_value.get() {
+                self.out_value.send(value);
+            }
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[group]
+    struct CleanGroup {
+        pub in_value: ReceivePort<u32>,
+        pub out_value: SendPort<u32>,
+    }
+
+    impl Group for CleanGroup {
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            network! {
+                module left: Filter("Left");
+                module right: Filter("Right");
+                fusion fusion: MaximumFusion("Fusion") <- [left.out_value, right.out_value];
+
+                self.in_value -> left.in_value;
+                self.in_value -> right.in_value;
+                fusion.output -> self.out_value;
+                left inhibits right;
+                characteristic fusion;
+            }
+        }
+    }
+
+    #[group]
+    struct FaultyGroup {
+        pub out_value: SendPort<u32>,
+        pub out_single: SendPort<u32>,
+    }
+
+    impl Group for FaultyGroup {
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            let off = SendPort::default();
+            off.send(MetaSignal::HIGH);
+            network! {
+                module unconnected: Filter("Unconnected");
+                module a: Producer("A");
+                module b: Producer("B");
+                module blocked: Producer("Blocked");
+                fusion fusion: MaximumFusion("Fusion") <- [a.out_value, b.out_value];
+                fusion single: MaximumFusion("Single") <- [blocked.out_value];
+
+                fusion.output -> self.out_value;
+                single.output -> self.out_single;
+                off -> blocked.inhibition;
+                a inhibits b;
+                b inhibits a;
+            }
+        }
+    }
+
+    #[group]
+    struct FusionCycleGroup {
+        pub out_value: SendPort<u32>,
+    }
+
+    impl Group for FusionCycleGroup {
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            network! {
+                module a: Producer("A");
+                module b: Producer("B");
+                fusion selection: MaximumFusion("Fusion") <- [a.out_value, b.out_value];
+
+                selection.output -> self.out_value;
+                selection inhibits a;
+            }
+        }
+    }
+
+    #[group]
+    struct InhibitedGroup {
+        pub out_value: SendPort<u32>,
+    }
+
+    impl Group for InhibitedGroup {
+        fn init(&mut self, cycle_time: Duration, parent: &Parent) {
+            network! {
+                module producer: Producer("Producer");
+
+                producer.out_value -> self.out_value;
+                characteristic producer;
+            }
+        }
+    }
+
+    fn check<G: Group + Send + 'static>() -> Vec<NetworkIssue> {
+        let parent = Parent { path: "Main".to_string(), ..Default::default() };
+        let group = BehaviorGroup::<G>::with_name("Checked", Duration::from_millis(1), &parent);
+        parent.request_stop();
+        group.network().check()
+    }
+
+    #[test]
+    fn test_clean_network() {
+        assert_eq!(check::<CleanGroup>(), []);
+    }
+
+    #[test]
+    fn test_external_inhibition() {
+        // the inhibition of the group is driven by the application and passed on to its characteristic module
+        let parent = Parent { path: "Main".to_string(), ..Default::default() };
+        let group = BehaviorGroup::<InhibitedGroup>::with_name("Checked", Duration::from_millis(1), &parent);
+        let inhibition = SendPort::default();
+        inhibition.send(MetaSignal::HIGH);
+        group.inhibition.connect_to_source(&inhibition).unwrap();
+        parent.request_stop();
+        assert_eq!(group.network().check(), []);
+    }
+
+    #[test]
+    fn test_network_issues() {
+        assert_eq!(check::<FaultyGroup>(), [
+            NetworkIssue::UnconnectedInput { node: "Main/Checked/Unconnected".to_string(), port: "in_value".to_string() },
+            NetworkIssue::UnreadOutput { node: "Main/Checked/Unconnected".to_string(), port: "out_value".to_string() },
+            NetworkIssue::SingleInputFusion { fusion: "Main/Checked/Single".to_string(), inputs: 1 },
+            NetworkIssue::InhibitedAtSnapshot { module: "Main/Checked/Blocked".to_string() },
+            NetworkIssue::InhibitionCycle { nodes: vec!["Main/Checked/A".to_string(), "Main/Checked/B".to_string()] },
+        ]);
+        assert_eq!(
+            NetworkIssue::SingleInputFusion { fusion: "Main/Checked/Single".to_string(), inputs: 1 }.to_string(),
+            "fusion module Main/Checked/Single has only 1 input"
+        );
+    }
+
+    #[test]
+    fn test_fusion_inhibition_cycle() {
+        // the activity of the fusion module depends on the activity of the module it inhibits
+        assert_eq!(check::<FusionCycleGroup>(), [
+            NetworkIssue::InhibitionCycle { nodes: vec!["Main/Checked/A".to_string(), "Main/Checked/Fusion".to_string()] },
+        ]);
+    }
+}
@@ -0,0 +1,771 @@
+use std::{collections::{BTreeMap, HashSet}, fmt::Display, path::Path, sync::Mutex};
+
+use data_types::{pose_data::Rotation2D, si_units::{Radian, SiValue}};
+use rust_ib2c_shared_data::ParameterInfo;
+use typenum::Integer;
+
+use crate::{port::ParameterPort, traits::PortSerialization};
+
+/// Value of a parameter in a parameter file.
+pub type ParameterValue = serde_json::Value;
+
+/// File formats of parameter files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ParameterFormat {
+    /// Determines the format from the file extension (`.toml`, `.json`, `.yaml` or `.yml`).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(ParameterFormat::Toml),
+            "json" => Some(ParameterFormat::Json),
+            "yaml" | "yml" => Some(ParameterFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned when loading parameters fails.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterError {
+    /// The parameter file could not be read, parsed or written.
+    File {
+        path: String,
+        message: String,
+    },
+    /// No module with this path exists in the system.
+    UnknownModule(String),
+    /// The module has no parameter with this name.
+    UnknownParameter {
+        module: String,
+        parameter: String,
+    },
+    /// The value can not be converted to the type of the parameter, e.g. because of a unit mismatch.
+    InvalidValue {
+        module: String,
+        parameter: String,
+        message: String,
+    },
+    /// Several errors occurred while loading the parameters of a system.
+    Multiple(Vec<ParameterError>),
+}
+
+impl Display for ParameterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParameterError::File { path, message } => write!(f, "failed to access parameter file '{}': {}", path, message),
+            ParameterError::UnknownModule(module) => write!(f, "unknown module '{}'", module),
+            ParameterError::UnknownParameter { module, parameter } => write!(f, "module '{}' has no parameter '{}'", module, parameter),
+            ParameterError::InvalidValue { module, parameter, message } => write!(f, "invalid value for '{}' of module '{}': {}", parameter, module, message),
+            ParameterError::Multiple(errors) => {
+                for (index, error) in errors.iter().enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParameterError {}
+
+/// Error returned by [`ParameterPort::set`][crate::port::ParameterPort::set] if the value is outside of the declared limits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutOfRangeError {
+    pub value: String,
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+impl Display for OutOfRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is outside of the limits [{}, {}]", 
+            self.value, 
+            self.min.as_deref().unwrap_or("-inf"), 
+            self.max.as_deref().unwrap_or("inf"))
+    }
+}
+
+impl std::error::Error for OutOfRangeError {}
+
+/// Limits and display hints of a parameter, generated by `#[parameter(...)]` on `ParameterPort` fields of a `#[module]`.
+/// Limits and step size are given like values in parameter files, e.g. "10 cm".
+#[derive(Debug, Clone, Default)]
+pub struct ParameterDeclaration {
+    pub min: Option<ParameterValue>,
+    pub max: Option<ParameterValue>,
+    /// Step size of sliders in the monitor. Not enforced when setting the parameter.
+    pub step: Option<ParameterValue>,
+    pub description: Option<&'static str>,
+    /// Unit the value is displayed in by the monitor, e.g. "cm".
+    pub unit: Option<&'static str>,
+}
+
+/// Formats a parameter value for messages, strings are not quoted.
+pub(crate) fn display_parameter_value(value: &ParameterValue) -> String {
+    match value {
+        ParameterValue::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Conversion between values in parameter files and parameter types.
+pub trait ParseParameter: Sized {
+    fn parse_parameter(value: &ParameterValue) -> Result<Self, String>;
+
+    /// Converts the parameter to a value that can be parsed again by [`ParseParameter::parse_parameter`].
+    fn to_parameter_value(&self) -> ParameterValue;
+}
+
+macro_rules! ParseNumberParameter {
+    ($conversion:ident => $($t:ty),*) => {
+        $(
+            impl ParseParameter for $t {
+                fn parse_parameter(value: &ParameterValue) -> Result<Self, String> {
+                    value.$conversion()
+                        .and_then(|number| <$t>::try_from(number).ok())
+                        .ok_or_else(|| format!("expected {}, found {}", stringify!($t), value))
+                }
+
+                fn to_parameter_value(&self) -> ParameterValue {
+                    ParameterValue::from(*self)
+                }
+            }
+        )*
+    };
+}
+
+ParseNumberParameter!(as_i64 => i8, i16, i32, i64, isize);
+ParseNumberParameter!(as_u64 => u8, u16, u32, u64, usize);
+
+impl ParseParameter for f64 {
+    fn parse_parameter(value: &ParameterValue) -> Result<Self, String> {
+        value.as_f64().ok_or_else(|| format!("expected a number, found {}", value))
+    }
+
+    fn to_parameter_value(&self) -> ParameterValue {
+        ParameterValue::from(*self)
+    }
+}
+
+impl ParseParameter for f32 {
+    fn parse_parameter(value: &ParameterValue) -> Result<Self, String> {
+        f64::parse_parameter(value).map(|value| value as f32)
+    }
+
+    fn to_parameter_value(&self) -> ParameterValue {
+        ParameterValue::from(*self as f64)
+    }
+}
+
+impl ParseParameter for bool {
+    fn parse_parameter(value: &ParameterValue) -> Result<Self, String> {
+        value.as_bool().ok_or_else(|| format!("expected a bool, found {}", value))
+    }
+
+    fn to_parameter_value(&self) -> ParameterValue {
+        ParameterValue::from(*self)
+    }
+}
+
+impl ParseParameter for String {
+    fn parse_parameter(value: &ParameterValue) -> Result<Self, String> {
+        value.as_str().map(str::to_string).ok_or_else(|| format!("expected a string, found {}", value))
+    }
+
+    fn to_parameter_value(&self) -> ParameterValue {
+        ParameterValue::from(self.clone())
+    }
+}
+
+/// SI values are given as strings with unit, e.g. "1.5 m" or "150 cm". Dimensionless values can also be given as numbers.
+impl<L, M, T, A, K, Mol, Cd> ParseParameter for SiValue<L, M, T, A, K, Mol, Cd>
+where
+    L: Integer,
+    M: Integer,
+    T: Integer,
+    A: Integer,
+    K: Integer,
+    Mol: Integer,
+    Cd: Integer,
+{
+    fn parse_parameter(value: &ParameterValue) -> Result<Self, String> {
+        let text = match value {
+            ParameterValue::String(text) => text.clone(),
+            ParameterValue::Number(number) => number.to_string(),
+            _ => return Err(format!("expected a value with unit like \"1.5 m\", found {}", value)),
+        };
+        text.parse().map_err(|error| format!("{}", error))
+    }
+
+    fn to_parameter_value(&self) -> ParameterValue {
+        ParameterValue::from(self.format_in_base_units())
+    }
+}
+
+/// Rotations are given as angles, e.g. "90 deg" or "1.57 rad".
+impl ParseParameter for Rotation2D {
+    fn parse_parameter(value: &ParameterValue) -> Result<Self, String> {
+        Radian::parse_parameter(value).map(|angle| Rotation2D::from_angle(angle.as_value_in_base_units()))
+    }
+
+    fn to_parameter_value(&self) -> ParameterValue {
+        ParameterValue::from(format!("{} deg", self.as_degrees()))
+    }
+}
+
+/// Access of the `#[module]` macro to a `ParameterPort` field. Parameters of types implementing [`ParseParameter`]
+/// are loaded and read through [`LoadableParameter`], parameters of other types, e.g. structs deriving `PortSerialization`,
+/// fall back to [`OpaqueParameter`]. They can not be set from parameter files and are not saved to them.
+///
+/// The macro calls the methods on `&ParameterAccess(&port)`, so the implementation for `ParameterAccess`
+/// is preferred over the one for `&ParameterAccess` whenever its bounds are met.
+#[doc(hidden)]
+pub struct ParameterAccess<'a, T: PortSerialization>(pub &'a ParameterPort<T>);
+
+#[doc(hidden)]
+pub trait LoadableParameter {
+    fn load_value(&self, value: &ParameterValue) -> Result<(), String>;
+    fn reader(&self) -> Option<ParameterReader>;
+}
+
+impl<T> LoadableParameter for ParameterAccess<'_, T>
+where
+    T: ParseParameter + Default + PortSerialization + Send + Sync + 'static,
+{
+    fn load_value(&self, value: &ParameterValue) -> Result<(), String> {
+        let value = T::parse_parameter(value)?;
+        self.0.set(value).map_err(|error| error.to_string())
+    }
+
+    fn reader(&self) -> Option<ParameterReader> {
+        Some(self.0.reader())
+    }
+}
+
+#[doc(hidden)]
+pub trait OpaqueParameter {
+    fn load_value(&self, _value: &ParameterValue) -> Result<(), String> {
+        Err("parameters of this type can not be loaded from parameter files".to_string())
+    }
+
+    fn reader(&self) -> Option<ParameterReader> {
+        None
+    }
+}
+
+impl<T: PortSerialization> OpaqueParameter for &ParameterAccess<'_, T> {}
+
+/// Loading of parameters by name. Implemented by the `#[module]` macro for all `ParameterPort` fields.
+pub trait ParameterLoading {
+    /// Sets the parameter with the given name.
+    /// Returns `None` if the module has no parameter with this name.
+    fn load_parameter(&self, name: &str, value: &ParameterValue) -> Option<Result<(), String>>;
+
+    /// Returns readers of the current values of all parameters that can be saved to parameter files,
+    /// used to save the parameters of a running system.
+    fn parameter_readers(&self) -> Vec<(&'static str, ParameterReader)>;
+
+    /// Applies the limits and display hints declared with `#[parameter(...)]`.
+    /// Fails if a declaration is invalid or an initial value is outside of its limits.
+    fn declare_parameters(&mut self) -> Result<(), String>;
+
+    /// Returns the declared limits and display hints of all parameters that have a declaration.
+    fn parameter_info(&self) -> Vec<(String, ParameterInfo)>;
+
+    /// Returns the names of all parameters.
+    fn parameter_names(&self) -> Vec<&'static str>;
+
+    /// Returns the names of all parameters that changed in the last update of the ports.
+    fn changed_parameters(&self) -> Vec<&'static str>;
+}
+
+/// Reads the current value of a parameter, see [`ParameterPort::reader`][crate::port::ParameterPort::reader].
+pub type ParameterReader = Box<dyn Fn() -> Option<ParameterValue> + Send + Sync>;
+
+/// Parameter readers of all modules of a system, keyed by module path.
+#[derive(Default)]
+pub(crate) struct ParameterRegistry {
+    modules: Mutex<BTreeMap<String, Vec<(&'static str, ParameterReader)>>>,
+}
+
+impl ParameterRegistry {
+    /// Registers the parameters of a module. Replaces the parameters of a module that was registered with the same path before.
+    pub(crate) fn register<M: ParameterLoading>(&self, module_path: &str, module: &M) {
+        let readers = module.parameter_readers();
+        if readers.is_empty() {
+            return;
+        }
+        self.modules.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(module_path.to_string(), readers);
+    }
+
+    /// Reads the current values of all registered parameters.
+    pub(crate) fn snapshot(&self) -> ParameterSet {
+        let modules = self.modules.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let modules = modules.iter()
+            .map(|(module, readers)| {
+                let parameters = readers.iter()
+                    .filter_map(|(name, reader)| Some((name.to_string(), reader()?)))
+                    .collect();
+                (module.clone(), parameters)
+            })
+            .collect();
+        ParameterSet {
+            modules,
+            ..Default::default()
+        }
+    }
+}
+
+/// Parameter values loaded from a parameter file, keyed by module path and parameter name.
+///
+/// # Example
+/// ```toml
+/// ["MainGroup/VelocityControl/BreakOnObstacle"]
+/// par_min_distance = "150 cm"
+/// ```
+#[derive(Debug, Default)]
+pub struct ParameterSet {
+    modules: BTreeMap<String, BTreeMap<String, ParameterValue>>,
+    loaded_modules: Mutex<HashSet<String>>,
+    errors: Mutex<Vec<ParameterError>>,
+}
+
+impl ParameterSet {
+    /// Reads a TOML, JSON or YAML parameter file. The format is determined by the file extension.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ParameterError> {
+        let path = path.as_ref();
+        let file_error = |message: String| ParameterError::File { path: path.display().to_string(), message };
+        let format = ParameterFormat::from_path(path)
+            .ok_or_else(|| file_error("unsupported format, expected a .toml, .json, .yaml or .yml file".to_string()))?;
+        let content = std::fs::read_to_string(path).map_err(|error| file_error(error.to_string()))?;
+        Self::parse(&content, format).map_err(file_error)
+    }
+
+    /// Parses parameters in the given format.
+    pub fn parse(content: &str, format: ParameterFormat) -> Result<Self, String> {
+        let modules = match format {
+            ParameterFormat::Toml => toml::from_str(content).map_err(|error| error.to_string())?,
+            ParameterFormat::Json => serde_json::from_str(content).map_err(|error| error.to_string())?,
+            ParameterFormat::Yaml => serde_yaml_ng::from_str(content).map_err(|error| error.to_string())?,
+        };
+        Ok(Self {
+            modules,
+            ..Default::default()
+        })
+    }
+
+    /// Returns the value of a parameter of the module with the given path.
+    pub fn get(&self, module_path: &str, parameter: &str) -> Option<&ParameterValue> {
+        self.modules.get(module_path)?.get(parameter)
+    }
+
+    /// Serializes the parameters in the given format.
+    pub fn serialize(&self, format: ParameterFormat) -> Result<String, String> {
+        match format {
+            ParameterFormat::Toml => toml::to_string_pretty(&self.modules).map_err(|error| error.to_string()),
+            ParameterFormat::Json => serde_json::to_string_pretty(&self.modules).map_err(|error| error.to_string()),
+            ParameterFormat::Yaml => serde_yaml_ng::to_string(&self.modules).map_err(|error| error.to_string()),
+        }
+    }
+
+    /// Writes a TOML, JSON or YAML parameter file that can be loaded again with [`ParameterSet::from_file`].
+    /// The format is determined by the file extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ParameterError> {
+        let path = path.as_ref();
+        let file_error = |message: String| ParameterError::File { path: path.display().to_string(), message };
+        let format = ParameterFormat::from_path(path)
+            .ok_or_else(|| file_error("unsupported format, expected a .toml, .json, .yaml or .yml file".to_string()))?;
+        let content = self.serialize(format).map_err(file_error)?;
+        std::fs::write(path, content).map_err(|error| file_error(error.to_string()))
+    }
+
+    /// Sets the parameters of the module with the given path. Errors are collected and reported by [`ParameterSet::check`].
+    pub(crate) fn load<M: ParameterLoading>(&self, module_path: &str, module: &M) {
+        let Some(parameters) = self.modules.get(module_path) else {
+            return;
+        };
+        self.loaded_modules.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(module_path.to_string());
+        let mut errors = self.errors.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for (parameter, value) in parameters {
+            match module.load_parameter(parameter, value) {
+                Some(Ok(())) => {}
+                Some(Err(message)) => errors.push(ParameterError::InvalidValue {
+                    module: module_path.to_string(),
+                    parameter: parameter.clone(),
+                    message,
+                }),
+                None => errors.push(ParameterError::UnknownParameter {
+                    module: module_path.to_string(),
+                    parameter: parameter.clone(),
+                }),
+            }
+        }
+    }
+
+    /// Reports errors of loaded parameters and module paths of the file that do not exist in the system.
+    pub(crate) fn check(&self) -> Result<(), ParameterError> {
+        let loaded_modules = self.loaded_modules.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut errors = self.errors.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+        errors.extend(self.modules.keys()
+            .filter(|module| !loaded_modules.contains(*module))
+            .map(|module| ParameterError::UnknownModule(module.clone())));
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(ParameterError::Multiple(errors)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::{atomic::{AtomicUsize, Ordering}, Arc}, time::Duration};
+
+    use data_types::si_units::Distance;
+    use rust_ib2c_shared_data::DisplayUnit;
+
+    use crate::prelude::*;
+    use super::*;
+
+    #[module]
+    struct Braking {
+        par_min_distance: ParameterPort<Distance>,
+        par_enabled: ParameterPort<bool>,
+        par_gain: ParameterPort<f64>,
+    }
+
+    impl Module for Braking {
+        fn transfere(&mut self) {}
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[module]
+    struct Limited {
+        #[parameter(min = "10 cm", max = "5 m", step = "10 cm", unit = "cm", description = "Braking distance")]
+        par_distance: ParameterPort<Distance>,
+        #[parameter(min = 0, description = "Number of retries")]
+        par_retries: ParameterPort<i32>,
+    }
+
+    impl Module for Limited {
+        fn transfere(&mut self) {}
+
+        fn init() -> Self {
+            Self {
+                par_distance: ParameterPort::with_value(Distance::meters(1.0)),
+                ..Default::default()
+            }
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[module]
+    struct OutOfLimits {
+        #[parameter(max = -1.0)]
+        par_gain: ParameterPort<f64>,
+    }
+
+    impl Module for OutOfLimits {
+        fn transfere(&mut self) {}
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[derive(PortSerialization, Clone, Default)]
+    struct Gains {
+        p: f64,
+        d: f64,
+    }
+
+    #[module]
+    struct Controller {
+        par_gains: ParameterPort<Gains>,
+        par_gain: ParameterPort<f64>,
+    }
+
+    impl Module for Controller {
+        fn transfere(&mut self) {}
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    static CHANGED_PARAMETERS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    #[module]
+    struct Tuned {
+        par_gain: ParameterPort<f64>,
+        par_offset: ParameterPort<f64>,
+    }
+
+    impl Module for Tuned {
+        fn transfere(&mut self) {}
+
+        fn on_parameter_changed(&mut self, name: &str) {
+            CHANGED_PARAMETERS.lock().unwrap().push(name.to_string());
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    #[module]
+    struct Observed {
+        par_gain: ParameterPort<f64>,
+        changes: Arc<Mutex<Vec<(String, f64, usize)>>>,
+        cycles: Arc<AtomicUsize>,
+    }
+
+    impl Module for Observed {
+        fn transfere(&mut self) {
+            self.cycles.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_parameter_changed(&mut self, name: &str) {
+            let cycles = self.cycles.load(Ordering::SeqCst);
+            self.changes.lock().unwrap().push((name.to_string(), self.par_gain.get(), cycles));
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    /// Cycles of the faulting instance when the parameter was reported.
+    static RECALIBRATIONS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+    #[module]
+    struct Recalibrating {
+        par_gain: ParameterPort<f64>,
+        cycles: u32,
+    }
+
+    impl Module for Recalibrating {
+        // the cycles are computed in try_transfere to report errors
+        fn transfere(&mut self) {}
+
+        fn try_transfere(&mut self) -> Result<(), ModuleError> {
+            self.cycles += 1;
+            if self.cycles == 2 {
+                return Err(ModuleError::new("second cycle"));
+            }
+            Ok(())
+        }
+
+        fn on_parameter_changed(&mut self, _name: &str) {
+            RECALIBRATIONS.lock().unwrap().push(self.cycles);
+        }
+
+        fn target_rating(&self) -> MetaSignal {
+            MetaSignal::HIGH
+        }
+    }
+
+    fn load(content: &str, format: ParameterFormat) -> (BehaviorModule<Braking>, Result<(), ParameterError>) {
+        let parameters = Arc::new(ParameterSet::parse(content, format).unwrap());
+        let parent = Parent {
+            path: "Main".to_string(),
+            parameters: Some(Arc::clone(&parameters)),
+            ..Default::default()
+        };
+        let mut module = BehaviorModule::<Braking>::with_name("Braking", Duration::from_millis(1), &parent);
+        module.update_all_ports();
+        (module, parameters.check())
+    }
+
+    #[test]
+    fn test_load_parameters() {
+        let toml = r#"
+            ["Main/Braking"]
+            par_min_distance = "150 cm"
+            par_enabled = true
+            par_gain = 0.5
+        "#;
+        let (module, result) = load(toml, ParameterFormat::Toml);
+        assert_eq!(result, Ok(()));
+        assert_eq!(module.par_min_distance.get(), Distance::meters(1.5));
+        assert!(module.par_enabled.get());
+        assert_eq!(module.par_gain.get(), 0.5);
+
+        let yaml = "Main/Braking:\n  par_min_distance: 2 m\n";
+        let (module, result) = load(yaml, ParameterFormat::Yaml);
+        assert_eq!(result, Ok(()));
+        assert_eq!(module.par_min_distance.get(), Distance::meters(2.0));
+    }
+
+    #[test]
+    fn test_save_parameters() {
+        let parent = Parent {
+            path: "Main".to_string(),
+            ..Default::default()
+        };
+        let module = BehaviorModule::<Braking>::with_name("Braking", Duration::from_millis(1), &parent);
+        module.par_min_distance.set(Distance::meters(2.5)).unwrap();
+        module.par_gain.set(0.25).unwrap();
+        let saved = parent.live_parameters.snapshot();
+        assert_eq!(saved.get("Main/Braking", "par_min_distance"), Some(&ParameterValue::from("2.5 m")));
+
+        for format in [ParameterFormat::Toml, ParameterFormat::Json, ParameterFormat::Yaml] {
+            let (module, result) = load(&saved.serialize(format).unwrap(), format);
+            assert_eq!(result, Ok(()));
+            assert_eq!(module.par_min_distance.get(), Distance::meters(2.5));
+            assert_eq!(module.par_gain.get(), 0.25);
+            assert!(!module.par_enabled.get());
+        }
+    }
+
+    #[test]
+    fn test_struct_parameters() {
+        let toml = r#"
+            ["Main/Controller"]
+            par_gain = 0.5
+            par_gains = { p = 1.0, d = 0.1 }
+        "#;
+        let parameters = Arc::new(ParameterSet::parse(toml, ParameterFormat::Toml).unwrap());
+        let parent = Parent {
+            path: "Main".to_string(),
+            parameters: Some(Arc::clone(&parameters)),
+            ..Default::default()
+        };
+        let mut module = BehaviorModule::<Controller>::with_name("Controller", Duration::from_millis(1), &parent);
+        module.update_all_ports();
+        assert_eq!(module.par_gain.get(), 0.5);
+        assert_eq!(module.par_gains.get().p, 0.0);
+        let Err(ParameterError::InvalidValue { parameter, .. }) = parameters.check() else {
+            panic!("expected an invalid value");
+        };
+        assert_eq!(parameter, "par_gains");
+
+        // struct parameters are not saved
+        let saved = parent.live_parameters.snapshot();
+        assert_eq!(saved.get("Main/Controller", "par_gain"), Some(&ParameterValue::from(0.5)));
+        assert_eq!(saved.get("Main/Controller", "par_gains"), None);
+    }
+
+    #[test]
+    fn test_parameter_limits() {
+        let parameters = Arc::new(ParameterSet::parse("[\"Main/Limited\"]\npar_distance = \"2 cm\"", ParameterFormat::Toml).unwrap());
+        let parent = Parent {
+            path: "Main".to_string(),
+            parameters: Some(Arc::clone(&parameters)),
+            ..Default::default()
+        };
+        let module = BehaviorModule::<Limited>::with_name("Limited", Duration::from_millis(1), &parent);
+        let Err(ParameterError::InvalidValue { message, .. }) = parameters.check() else {
+            panic!("expected an invalid value");
+        };
+        assert_eq!(message, "0.02 m is outside of the limits [0.1 m, 5 m]");
+
+        assert_eq!(module.par_distance.set(Distance::meters(2.0)), Ok(()));
+        assert!(module.par_distance.set(Distance::meters(-1.0)).is_err());
+        assert!(module.par_distance.set(Distance::meters(f64::NAN)).is_err());
+        assert_eq!(module.par_retries.set(-1), Err(OutOfRangeError { value: "-1".to_string(), min: Some("0".to_string()), max: None }));
+        assert_eq!(module.par_retries.set(3), Ok(()));
+
+        let info = module.parameter_info();
+        assert_eq!(info.len(), 2);
+        let (name, distance) = &info[0];
+        assert_eq!(name, "par_distance");
+        assert_eq!(distance.min.as_ref().and_then(PortData::as_f64), Some(0.1));
+        assert_eq!(distance.step.as_ref().and_then(PortData::as_f64), Some(0.1));
+        assert_eq!(distance.description.as_deref(), Some("Braking distance"));
+        assert_eq!(distance.unit, Some(DisplayUnit { symbol: "cm".to_string(), factor: 0.01 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "par_gain: initial value 0.0 is outside of the limits [-inf, -1.0]")]
+    fn test_initial_value_out_of_limits() {
+        BehaviorModule::<OutOfLimits>::with_name("OutOfLimits", Duration::from_millis(1), &Parent::default());
+    }
+
+    #[test]
+    fn test_parameter_changed() {
+        let parent = Parent::default();
+        let mut module = BehaviorModule::<Tuned>::with_name("Tuned", Duration::from_millis(1), &parent);
+        module.update_all_ports();
+        assert!(!module.par_gain.changed());
+
+        module.par_gain.set(2.0).unwrap();
+        module.update_all_ports();
+        assert!(module.par_gain.changed());
+        assert!(!module.par_offset.changed());
+        assert_eq!(module.changed_parameters(), vec!["par_gain"]);
+
+        module.update_all_ports();
+        assert!(!module.par_gain.changed());
+
+        // every parameter is reported once before the first cycle, including the one set before the spawn
+        module.par_offset.set(1.0).unwrap();
+        module.spawn();
+        assert!(crate::wait_until(|| CHANGED_PARAMETERS.lock().unwrap().len() >= 2));
+        parent.request_stop();
+        assert_eq!(*CHANGED_PARAMETERS.lock().unwrap(), vec!["par_gain".to_string(), "par_offset".to_string()]);
+    }
+
+    #[test]
+    fn test_loaded_parameter_changed() {
+        let parameters = Arc::new(ParameterSet::parse("[\"Main/Observed\"]\npar_gain = 2.0", ParameterFormat::Toml).unwrap());
+        let parent = Parent {
+            path: "Main".to_string(),
+            parameters: Some(Arc::clone(&parameters)),
+            ..Default::default()
+        };
+        let module = BehaviorModule::<Observed>::with_name("Observed", Duration::from_millis(1), &parent);
+        let changes = Arc::clone(&module.changes);
+        let cycles = Arc::clone(&module.cycles);
+        module.spawn();
+
+        // the loaded value is reported once, before the module computes anything
+        assert!(crate::wait_until(|| cycles.load(Ordering::SeqCst) >= 5));
+        parent.request_stop();
+        assert_eq!(*changes.lock().unwrap(), vec![("par_gain".to_string(), 2.0, 0)]);
+    }
+
+    #[test]
+    fn test_parameter_changed_after_restart() {
+        let parent = Parent::default();
+        let mut module = BehaviorModule::<Recalibrating>::with_name("Recalibrating", Duration::from_millis(1), &parent);
+        module.set_fault_policy(FaultPolicy::Restart);
+        module.spawn();
+
+        // every new instance is told about the parameter before its first cycle
+        assert!(crate::wait_until(|| RECALIBRATIONS.lock().unwrap().len() >= 3));
+        parent.request_stop();
+        assert!(RECALIBRATIONS.lock().unwrap().iter().all(|cycles| *cycles == 0));
+    }
+
+    #[test]
+    fn test_parameter_errors() {
+        let json = r#"{
+            "Main/Braking": { "par_min_distance": "2 s", "par_max_distance": "2 m" },
+            "Main/Steering": { "par_gain": 1.0 }
+        }"#;
+        let (module, result) = load(json, ParameterFormat::Json);
+        assert_eq!(module.par_min_distance.get(), Distance::ZERO);
+        let Err(ParameterError::Multiple(errors)) = result else {
+            panic!("expected multiple errors");
+        };
+        assert!(matches!(&errors[0], ParameterError::UnknownParameter { parameter, .. } if parameter == "par_max_distance"));
+        assert!(matches!(&errors[1], ParameterError::InvalidValue { parameter, .. } if parameter == "par_min_distance"));
+        assert_eq!(errors[2], ParameterError::UnknownModule("Main/Steering".to_string()));
+        assert_eq!(errors[1].to_string(), "invalid value for 'par_min_distance' of module 'Main/Braking': expected a value in [m], found a value in [s]");
+    }
+}
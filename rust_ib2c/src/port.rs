@@ -1,10 +1,12 @@
-use std::{ops::Deref, sync::{Arc, RwLock}};
+use std::{fmt::Display, ops::Deref, sync::{atomic::{AtomicUsize, Ordering}, Arc, Condvar, Mutex, MutexGuard, RwLock, RwLockReadGuard, Weak}, time::Duration};
 
-use rust_ib2c_shared_data::PortData;
-use data_types::si_units::SiValue;
+use arc_swap::{ArcSwap, ArcSwapOption};
+
+use rust_ib2c_shared_data::{DisplayUnit, ParameterInfo, PortData, PortDirection};
+use data_types::{pose_data::{Rotation2D, Rotation3D, Vector}, si_units::SiValue};
 use typenum::Integer;
 
-use crate::{prelude::MetaSignal, traits::PortSerialization};
+use crate::{parameters::{display_parameter_value, OutOfRangeError, ParameterDeclaration, ParameterReader, ParameterValue, ParseParameter}, prelude::MetaSignal, traits::{PortField, PortSerialization}};
 
 macro_rules! SerializePortData {
     ($t:ty, $conversion:expr) => {
@@ -45,48 +47,246 @@ where
     }
 }
 
-struct PortBuffer<T: PortSerialization> {
-    buffer: Option<Arc<T>>,
+/// Serialized as angle in degrees.
+impl PortSerialization for Rotation2D {
+    fn serialize_port_data(&self) -> PortData {
+        PortData::SiValue {
+            value: self.as_degrees(),
+            unit: " [°]".to_string(),
+        }
+    }
+}
+
+/// Serialized as quaternion (w, x, y, z) and euler angles (x, y, z) in degrees.
+impl PortSerialization for Rotation3D {
+    fn serialize_port_data(&self) -> PortData {
+        let (w, x, y, z) = self.as_quaternion();
+        PortData::Struct {
+            fields: vec![
+                ("quaternion".to_string(), PortData::Vector {
+                    components: vec![w, x, y, z],
+                    unit: String::new(),
+                }),
+                ("euler".to_string(), PortData::Vector {
+                    components: vec![
+                        self.x_rotation().to_degrees(),
+                        self.y_rotation().to_degrees(),
+                        self.z_rotation().to_degrees(),
+                    ],
+                    unit: " [°]".to_string(),
+                }),
+            ],
+        }
+    }
+}
+
+impl<T: PortSerialization> PortSerialization for Vec<T> {
+    fn serialize_port_data(&self) -> PortData {
+        PortData::List(self.iter().map(PortSerialization::serialize_port_data).collect())
+    }
 }
 
-enum PortMode<T: PortSerialization> {
-    Buffer(PortBuffer<T>),
-    Passthrough(Port<T>),
+impl<T: PortSerialization, const N: usize> PortSerialization for [T; N] {
+    fn serialize_port_data(&self) -> PortData {
+        PortData::List(self.iter().map(PortSerialization::serialize_port_data).collect())
+    }
+}
+
+impl<T: PortSerialization> PortSerialization for Option<T> {
+    fn serialize_port_data(&self) -> PortData {
+        PortData::Optional(self.as_ref().map(|data| Box::new(data.serialize_port_data())))
+    }
+}
+
+macro_rules! SerializeTuple {
+    ($($name:ident : $index:tt),+) => {
+        impl<$($name: PortSerialization),+> PortSerialization for ($($name,)+) {
+            fn serialize_port_data(&self) -> PortData {
+                PortData::List(vec![$(self.$index.serialize_port_data()),+])
+            }
+        }
+    };
+}
+
+SerializeTuple!(A: 0);
+SerializeTuple!(A: 0, B: 1);
+SerializeTuple!(A: 0, B: 1, C: 2);
+SerializeTuple!(A: 0, B: 1, C: 2, D: 3);
+SerializeTuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+SerializeTuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+
+/// Vectors of numbers or [`SiValue`]s are serialized as [`PortData::Vector`], all other vectors as [`PortData::List`].
+impl<T: PortSerialization + Default + Copy, const N: usize> PortSerialization for Vector<T, N> {
+    fn serialize_port_data(&self) -> PortData {
+        let items: Vec<PortData> = self.as_array().iter().map(PortSerialization::serialize_port_data).collect();
+        let mut components = Vec::with_capacity(N);
+        let mut vector_unit = String::new();
+        for item in &items {
+            match item {
+                PortData::Float(value) => components.push(*value),
+                PortData::Int(value) => components.push(*value as f64),
+                PortData::Unsigned(value) => components.push(*value as f64),
+                PortData::SiValue { value, unit } => {
+                    components.push(*value);
+                    vector_unit = unit.clone();
+                }
+                _ => return PortData::List(items),
+            }
+        }
+        PortData::Vector { components, unit: vector_unit }
+    }
+}
+
+/// Connections of a port. Only accessed when ports are connected or disconnected.
+struct Connections<T: PortSerialization> {
+    source: Option<Port<T>>,
+    targets: Vec<Weak<PortNode<T>>>,
+}
+
+struct PortBuffer<T> {
+    data: ArcSwapOption<T>,
+    /// Lock of the [`SyncPorts`] the buffer belongs to.
+    sync: ArcSwapOption<RwLock<()>>,
+    /// Triggers notified when data is sent to the buffer.
+    triggers: ArcSwap<Vec<ChangeTrigger>>,
+}
+
+impl<T> PortBuffer<T> {
+    fn new(data: Option<Arc<T>>) -> Self {
+        Self {
+            data: ArcSwapOption::new(data),
+            sync: ArcSwapOption::empty(),
+            triggers: ArcSwap::from_pointee(Vec::new()),
+        }
+    }
+
+    fn add_trigger(&self, trigger: &ChangeTrigger) {
+        self.triggers.rcu(|triggers| {
+            let mut triggers = Vec::clone(triggers);
+            triggers.push(trigger.clone());
+            triggers
+        });
+    }
+
+    fn remove_trigger(&self, trigger: &ChangeTrigger) {
+        self.triggers.rcu(|triggers| {
+            let mut triggers = Vec::clone(triggers);
+            if let Some(index) = triggers.iter().position(|other| other.is_same_trigger(trigger)) {
+                triggers.remove(index);
+            }
+            triggers
+        });
+    }
+}
+
+struct PortNode<T: PortSerialization> {
+    /// Buffer of the port, used while it is not connected to a source.
+    buffer: Arc<PortBuffer<T>>,
+    /// Buffer at the end of the chain of sources. Updated whenever the chain changes,
+    /// so reading and sending never have to follow the chain.
+    resolved: ArcSwap<PortBuffer<T>>,
+    connections: Mutex<Connections<T>>,
+    /// Triggers watching the port. They are moved along when the resolved buffer changes.
+    watchers: Mutex<Vec<ChangeTrigger>>,
+    link: Arc<LinkState>,
+}
+
+/// Identifier of a port and of its current source, shared with the [`PortLink`]s of the port.
+struct LinkState {
+    id: usize,
+    /// Identifier of the source port, 0 if the port is not connected.
+    source: AtomicUsize,
+}
+
+static NEXT_PORT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Serializes changes of the connections between ports, so the cycle check of a connection
+/// can not be outdated by a concurrent connection before the ports are linked.
+static CONNECTION_GRAPH: Mutex<()> = Mutex::new(());
+
+/// Named reference to a port of a module or group, used to export the structure of a system.
+/// The source of the port is read when it is requested, so connections made later are included.
+#[derive(Clone)]
+pub struct PortLink {
+    pub name: String,
+    pub direction: PortDirection,
+    state: Arc<LinkState>,
+    /// Reads the current value of stimulation and inhibition ports.
+    meta_signal: Option<Arc<dyn Fn() -> Option<MetaSignal> + Send + Sync>>,
+}
+
+impl PortLink {
+    /// Identifier of the port, unique within the process.
+    pub fn id(&self) -> usize {
+        self.state.id
+    }
+
+    /// Identifier of the port this port is connected to, if any.
+    pub fn source_id(&self) -> Option<usize> {
+        match self.state.source.load(Ordering::Relaxed) {
+            0 => None,
+            id => Some(id),
+        }
+    }
+
+    /// Current value of a stimulation or inhibition port, `None` for other ports or if no value was sent yet.
+    pub fn meta_signal(&self) -> Option<MetaSignal> {
+        self.meta_signal.as_ref().and_then(|read| read())
+    }
 }
 
 /// Internal port structure used by [`SendPort`] and [`ReceivePort`]
 pub struct Port<T: PortSerialization> {
-    mode: Arc<RwLock<PortMode<T>>>,
+    node: Arc<PortNode<T>>,
 }
 
 impl<T: PortSerialization> Clone for Port<T> {
     fn clone(&self) -> Self {
         Self {
-            mode: Arc::clone(&self.mode),
+            node: Arc::clone(&self.node),
         }
     }
 }
 
 impl<T: PortSerialization> Port<T> {
+    fn new(data: Option<Arc<T>>) -> Self {
+        let buffer = Arc::new(PortBuffer::new(data));
+        Self {
+            node: Arc::new(PortNode {
+                resolved: ArcSwap::new(Arc::clone(&buffer)),
+                buffer,
+                connections: Mutex::new(Connections { source: None, targets: Vec::new() }),
+                watchers: Mutex::new(Vec::new()),
+                link: Arc::new(LinkState {
+                    id: NEXT_PORT_ID.fetch_add(1, Ordering::Relaxed),
+                    source: AtomicUsize::new(0),
+                }),
+            }),
+        }
+    }
+
+    fn link(&self, name: &str, direction: PortDirection) -> PortLink {
+        PortLink {
+            name: name.to_string(),
+            direction,
+            state: Arc::clone(&self.node.link),
+            meta_signal: None,
+        }
+    }
+
     fn send(&self, data: T) {
-        if let PortMode::Passthrough(source_port) = &*self.mode.read().unwrap() {
-            source_port.send(data);
-            return;
+        let buffer = self.node.resolved.load();
+        buffer.data.store(Some(Arc::new(data)));
+        for trigger in buffer.triggers.load().iter() {
+            trigger.notify();
         }
-        let mut writer = self.mode.write().unwrap();
-        *writer = PortMode::Buffer(PortBuffer {
-            buffer: Some(Arc::new(data)),
-        });
     }
 
     fn get(&self) -> Option<T> 
     where 
         T: Clone,
     {
-        match &*self.mode.read().unwrap() {
-            PortMode::Buffer(buffer) => buffer.buffer.as_ref().map(|data| (*data.as_ref()).clone()),
-            PortMode::Passthrough(source_port) => source_port.get(),
-        }
+        self.get_reference().map(|data| (*data).clone())
     }
     
     fn get_or_default(&self) -> T
@@ -97,17 +297,209 @@ impl<T: PortSerialization> Port<T> {
     }
 
     fn get_reference(&self) -> Option<Arc<T>> {
-        match &*self.mode.read().unwrap() {
-            PortMode::Buffer(buffer) => buffer.buffer.as_ref().map(Arc::clone),
-            PortMode::Passthrough(source_port) => source_port.get_reference(),
-        }
+        self.node.resolved.load().data.load_full()
+    }
+
+    fn connections(&self) -> MutexGuard<'_, Connections<T>> {
+        self.node.connections.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
     }
     
-    fn connect_to_source(&self, source: &Port<T>) {
-        *self.mode.write().unwrap() = PortMode::Passthrough(source.clone());
+    fn connect_to_source(&self, source: &Port<T>) -> Result<(), ConnectionError> {
+        let _graph = CONNECTION_GRAPH.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if self.is_same_port(source) || source.is_connected_to(self) {
+            return Err(ConnectionError::Cycle);
+        }
+        {
+            let mut connections = self.connections();
+            if connections.source.is_some() {
+                return Err(ConnectionError::AlreadyConnected);
+            }
+            if self.node.buffer.data.load().is_some() {
+                return Err(ConnectionError::AlreadySent);
+            }
+            connections.source = Some(source.clone());
+            self.node.link.source.store(source.node.link.id, Ordering::Relaxed);
+        }
+        let mut source_connections = source.connections();
+        source_connections.targets.retain(|target| target.strong_count() > 0);
+        source_connections.targets.push(Arc::downgrade(&self.node));
+        drop(source_connections);
+        self.resolve();
+        Ok(())
+    }
+
+    fn disconnect(&self) {
+        let _graph = CONNECTION_GRAPH.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(source) = self.connections().source.take() else {
+            return;
+        };
+        self.node.link.source.store(0, Ordering::Relaxed);
+        source.connections().targets.retain(|target| target.strong_count() > 0 && !std::ptr::eq(target.as_ptr(), Arc::as_ptr(&self.node)));
+        self.node.buffer.data.store(None);
+        self.resolve();
+    }
+
+    /// Updates the resolved buffer of this port and of all ports connected to it.
+    fn resolve(&self) {
+        let (resolved, targets) = {
+            let connections = self.connections();
+            let resolved = match &connections.source {
+                Some(source) => source.node.resolved.load_full(),
+                None => Arc::clone(&self.node.buffer),
+            };
+            (resolved, connections.targets.iter().filter_map(Weak::upgrade).collect::<Vec<_>>())
+        };
+        let previous = self.node.resolved.swap(Arc::clone(&resolved));
+        if !Arc::ptr_eq(&previous, &resolved) {
+            for trigger in self.node.watchers.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).iter() {
+                previous.remove_trigger(trigger);
+                resolved.add_trigger(trigger);
+            }
+        }
+        for node in targets {
+            Port { node }.resolve();
+        }
+    }
+
+    fn is_same_port(&self, other: &Port<T>) -> bool {
+        Arc::ptr_eq(&self.node, &other.node)
+    }
+
+    /// Returns true if `other` is part of the chain of sources of this port.
+    fn is_connected_to(&self, other: &Port<T>) -> bool {
+        let mut source = self.connections().source.clone();
+        while let Some(port) = source {
+            if port.is_same_port(other) {
+                return true;
+            }
+            source = port.connections().source.clone();
+        }
+        false
     }
 } 
 
+/// Error returned when two ports can not be connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionError {
+    /// The connection would connect a port to itself or create a loop of passthrough ports.
+    Cycle,
+    /// The port is already connected to a source. Call `disconnect` first to change its source.
+    /// Also returned when a group gets its activity from both a characteristic module and members.
+    AlreadyConnected,
+    /// Data was already sent to the port. Connecting it would discard the data and forward
+    /// further sends to the source, e.g. when connecting the output of a running module to another output.
+    AlreadySent,
+    /// The number of ports and sources of a [`ConnectPorts::connect_to_sources`] call does not match.
+    LengthMismatch {
+        ports: usize,
+        sources: usize,
+    },
+}
+
+impl Display for ConnectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionError::Cycle => write!(f, "connection would create a cycle"),
+            ConnectionError::AlreadyConnected => write!(f, "port is already connected to a source"),
+            ConnectionError::AlreadySent => write!(f, "data was already sent to the port"),
+            ConnectionError::LengthMismatch { ports, sources } => write!(f, "{} ports can not be connected to {} sources", ports, sources),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionError {}
+
+/// Notification that wakes up a waiting thread when data is sent to one of the watched ports.
+/// Used by [`BehaviorModule`][crate::behavior_module::BehaviorModule]s in [`ExecutionMode::OnChange`][crate::behavior_module::ExecutionMode::OnChange].
+#[derive(Clone, Default)]
+pub struct ChangeTrigger {
+    inner: Arc<TriggerState>,
+}
+
+#[derive(Default)]
+struct TriggerState {
+    changed: Mutex<bool>,
+    condvar: Condvar,
+    watched_ports: AtomicUsize,
+}
+
+impl ChangeTrigger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notifies the trigger whenever data is sent to the port, also after the port is reconnected.
+    pub fn watch<T: PortSerialization>(&self, port: &Port<T>) {
+        port.node.watchers.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(self.clone());
+        port.node.resolved.load().add_trigger(self);
+        self.inner.watched_ports.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of ports watched by the trigger.
+    pub fn watched_ports(&self) -> usize {
+        self.inner.watched_ports.load(Ordering::Relaxed)
+    }
+
+    pub fn notify(&self) {
+        *self.inner.changed.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = true;
+        self.inner.condvar.notify_all();
+    }
+
+    /// Waits until the trigger is notified or the timeout expires and resets the trigger.
+    /// Returns immediately if the trigger was notified since the last call.
+    /// Returns false if the timeout expired.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let changed = self.inner.changed.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (mut changed, _) = self.inner.condvar
+            .wait_timeout_while(changed, timeout, |changed| !*changed)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        std::mem::replace(&mut *changed, false)
+    }
+
+    fn is_same_trigger(&self, other: &ChangeTrigger) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+/// Publishes data on several ports atomically. 
+/// 
+/// Modules read all ports of a group in the same `update_all_ports` call and see either all or none 
+/// of the data sent in one [`SyncPorts::publish`] call, e.g. the left and right distance sensor values 
+/// of the same measurement. Ports that are not part of a group are read without locking.
+#[derive(Clone, Default)]
+pub struct SyncPorts {
+    lock: Arc<RwLock<()>>,
+}
+
+impl SyncPorts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the buffer the port sends to. Add ports after connecting them,
+    /// the group belongs to the buffer at the end of the port's chain of sources.
+    pub fn add<T: PortSerialization>(&self, port: &Port<T>) {
+        port.node.resolved.load().sync.store(Some(Arc::clone(&self.lock)));
+    }
+
+    /// Sends the data of all ports in `publish` as one update.
+    pub fn publish<R>(&self, publish: impl FnOnce() -> R) -> R {
+        let _guard = self.lock.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        publish()
+    }
+
+    /// Locks the groups for reading, so ports of the groups can be updated consistently. 
+    /// Used by `update_all_ports` of modules and groups.
+    pub fn read_all(groups: &mut Vec<SyncPorts>) -> Vec<RwLockReadGuard<'_, ()>> {
+        // a fixed locking order prevents deadlocks between modules reading several groups
+        groups.sort_by_key(|group| Arc::as_ptr(&group.lock));
+        groups.dedup_by(|a, b| Arc::ptr_eq(&a.lock, &b.lock));
+        groups.iter()
+            .map(|group| group.lock.read().unwrap_or_else(|poisoned| poisoned.into_inner()))
+            .collect()
+    }
+}
+
 /// Sending port used to send data to connected [`ReceivePort`]s
 pub struct SendPort<T: PortSerialization> {
     inner: Port<T>,
@@ -138,9 +530,7 @@ impl<T: PortSerialization> Deref for ReceivePort<T> {
 impl<T: PortSerialization> Default for SendPort<T> {
     fn default() -> Self {
         Self {
-            inner: Port {
-                mode: Arc::new(RwLock::new(PortMode::Buffer(PortBuffer { buffer: None }))),
-            },
+            inner: Port::new(None),
         }
     }
 }
@@ -155,9 +545,7 @@ impl<T: PortSerialization> Clone for SendPort<T> {
 impl<T: PortSerialization> Default for ReceivePort<T> {
     fn default() -> Self {
         Self {
-            inner: Port {
-                mode: Arc::new(RwLock::new(PortMode::Buffer(PortBuffer { buffer: None }))),
-            },
+            inner: Port::new(None),
             buffer: None,
         }
     }
@@ -179,13 +567,23 @@ impl<T: PortSerialization> SendPort<T> {
     }
 
     /// Connect this [`SendPort`] to a source [`Port`] ([`SendPort`] or [`ReceivePort`])
-    pub fn connect_to_source(&self, source: &Port<T>) {
-        self.inner.connect_to_source(source);
+    pub fn connect_to_source(&self, source: &Port<T>) -> Result<(), ConnectionError> {
+        self.inner.connect_to_source(source)
     }
 
     /// Connect this [`SendPort`] as a source to a target [`Port`] ([`SendPort`] or [`ReceivePort`])
-    pub fn connect_as_source(&self, target: &Port<T>) {
-        target.connect_to_source(&self.inner);
+    pub fn connect_as_source(&self, target: &Port<T>) -> Result<(), ConnectionError> {
+        target.connect_to_source(&self.inner)
+    }
+
+    /// Remove the connection to the source of this [`SendPort`]. Does nothing if it is not connected.
+    pub fn disconnect(&self) {
+        self.inner.disconnect();
+    }
+
+    /// Returns true if this [`SendPort`] is connected to a source and forwards its data.
+    pub fn has_source(&self) -> bool {
+        self.inner.connections().source.is_some()
     }
 
     /// Get the last sent data 
@@ -209,15 +607,32 @@ impl<T: PortSerialization> SendPort<T> {
     }
 }
 
+impl ReceivePort<MetaSignal> {
+    /// Link to a stimulation or inhibition port, including its current value. Used by the `#[ports]` macro.
+    pub fn meta_signal_link(&self, name: &str) -> PortLink {
+        // the link must not keep the port alive
+        let node = Arc::downgrade(&self.inner.node);
+        PortLink {
+            meta_signal: Some(Arc::new(move || Port { node: node.upgrade()? }.get())),
+            ..self.link(name, PortDirection::Input)
+        }
+    }
+}
+
 impl<T: PortSerialization> ReceivePort<T> {
     /// Connect this [`ReceivePort`] to a source [`Port`] ([`SendPort`] or [`ReceivePort`])
-    pub fn connect_to_source(&self, source: &Port<T>) {
-        self.inner.connect_to_source(source);
+    pub fn connect_to_source(&self, source: &Port<T>) -> Result<(), ConnectionError> {
+        self.inner.connect_to_source(source)
     }
 
     /// Connect this [`ReceivePort`] as a source to a target [`Port`] ([`SendPort`] or [`ReceivePort`])
-    pub fn connect_as_source(&self, target: &Port<T>) {
-        target.connect_to_source(&self.inner);
+    pub fn connect_as_source(&self, target: &Port<T>) -> Result<(), ConnectionError> {
+        target.connect_to_source(&self.inner)
+    }
+
+    /// Remove the connection to the source of this [`ReceivePort`]. Does nothing if it is not connected.
+    pub fn disconnect(&self) {
+        self.inner.disconnect();
     }
 
     /// Update the internal buffer with the latest data from the connected SendPort
@@ -227,6 +642,11 @@ impl<T: PortSerialization> ReceivePort<T> {
         self.buffer = self.inner.get_reference();
     }
 
+    /// Returns the [`SyncPorts`] group of the connected source, if it belongs to one.
+    pub fn sync_group(&self) -> Option<SyncPorts> {
+        self.inner.node.resolved.load().sync.load_full().map(|lock| SyncPorts { lock })
+    }
+
     /// Get the last received data from the internal buffer
     pub fn get(&self) -> Option<T> 
     where 
@@ -251,34 +671,116 @@ impl<T: PortSerialization> ReceivePort<T> {
     }
 }
 
+/// Checks that a value is within the declared limits of a parameter.
+type LimitCheck<T> = Box<dyn Fn(&T) -> Result<(), OutOfRangeError> + Send + Sync>;
 
 pub struct ParameterPort<T: PortSerialization> {
     inner: Port<T>,
     buffer: Arc<T>,
+    changed: bool,
+    limits: Option<LimitCheck<T>>,
+    info: Option<ParameterInfo>,
 }
 
 impl<T: Default + PortSerialization> ParameterPort<T> {
-    // Set the parameter value.
-    pub fn set(&self, data: T) {
+    /// Set the parameter value. Values outside of the declared limits are rejected.
+    pub fn set(&self, data: T) -> Result<(), OutOfRangeError> {
+        if let Some(limits) = &self.limits {
+            limits(&data)?;
+        }
         self.inner.send(data);
+        Ok(())
     }
 
     /// Create a new ParameterPort with an initial value
     pub fn with_value(value: T) -> Self {
         let val = Arc::new(value);
         Self {
-            inner: Port {
-                mode: Arc::new(RwLock::new(PortMode::Buffer(PortBuffer { buffer: Some(val.clone()) }))),
-            },
+            inner: Port::new(Some(val.clone())),
             buffer: val,
+            changed: false,
+            limits: None,
+            info: None,
+        }
+    }
+
+    /// Declares limits and display hints of the parameter. Called for fields with `#[parameter(...)]` by [`BehaviorModule`][crate::behavior_module::BehaviorModule].
+    /// Fails if a value of the declaration can not be parsed or the current value is outside of the limits.
+    pub fn declare(&mut self, declaration: ParameterDeclaration) -> Result<(), String>
+    where
+        T: ParseParameter + PartialOrd + Send + Sync + 'static,
+    {
+        let parse = |value: Option<ParameterValue>| value.map(|value| T::parse_parameter(&value)).transpose();
+        let min = parse(declaration.min)?;
+        let max = parse(declaration.max)?;
+        let step = parse(declaration.step)?;
+        let unit = declaration.unit.map(|symbol| {
+            let one = T::parse_parameter(&ParameterValue::from(format!("1 {}", symbol)))?;
+            let factor = one.serialize_port_data().as_f64()
+                .ok_or_else(|| format!("unit '{}' can only be used for numeric parameters", symbol))?;
+            Ok::<_, String>(DisplayUnit { symbol: symbol.to_string(), factor })
+        }).transpose()?;
+        self.info = Some(ParameterInfo {
+            min: min.as_ref().map(PortSerialization::serialize_port_data),
+            max: max.as_ref().map(PortSerialization::serialize_port_data),
+            step: step.as_ref().map(PortSerialization::serialize_port_data),
+            description: declaration.description.map(str::to_string),
+            unit,
+        });
+        if min.is_none() && max.is_none() {
+            self.limits = None;
+            return Ok(());
+        }
+
+        let display = |value: &T| display_parameter_value(&value.to_parameter_value());
+        let min_text = min.as_ref().map(display);
+        let max_text = max.as_ref().map(display);
+        let limits: LimitCheck<T> = Box::new(move |value| {
+            // values that can not be compared, like NaN, are rejected
+            let below = min.as_ref().is_some_and(|min| value.partial_cmp(min).is_none_or(|ordering| ordering.is_lt()));
+            let above = max.as_ref().is_some_and(|max| value.partial_cmp(max).is_none_or(|ordering| ordering.is_gt()));
+            if below || above {
+                return Err(OutOfRangeError {
+                    value: display(value),
+                    min: min_text.clone(),
+                    max: max_text.clone(),
+                });
+            }
+            Ok(())
+        });
+        if let Some(value) = self.inner.get_reference() {
+            limits(&value).map_err(|error| format!("initial value {}", error))?;
         }
+        self.limits = Some(limits);
+        Ok(())
+    }
+
+    /// Returns the declared limits and display hints of the parameter.
+    pub fn info(&self) -> Option<&ParameterInfo> {
+        self.info.as_ref()
     }
 
     /// Update the internal buffer with the latest data from the connected SendPort
     /// Is called automatically when used inside a [`BehaviorModule`][`crate::behavior_module::BehaviorModule`]
     /// and does not need to be called manually.
     pub fn update(&mut self) {
-        self.buffer = self.inner.get_reference().unwrap_or_else(|| Arc::new(T::default()));
+        match self.inner.get_reference() {
+            Some(value) => {
+                self.changed = !Arc::ptr_eq(&value, &self.buffer);
+                self.buffer = value;
+            }
+            None => {
+                self.changed = false;
+                self.buffer = Arc::new(T::default());
+            }
+        }
+    }
+
+    /// Returns true if a new value was set since the previous [`update`][ParameterPort::update], even if it is equal to the old value.
+    /// Inside a [`BehaviorModule`][crate::behavior_module::BehaviorModule] [`Module::on_parameter_changed`][crate::traits::Module::on_parameter_changed]
+    /// is called for all changed parameters after the ports were updated.
+    pub fn changed(&self) -> bool {
+        self.changed
     }
 
     /// Get the current parameter value from the internal buffer
@@ -292,20 +794,214 @@ impl<T: Default + PortSerialization> ParameterPort<T> {
     pub fn get_reference(&self) -> &T {
         self.buffer.deref()
     }
+
+    /// Returns a reader of the latest value of the parameter that can be used from other threads.
+    pub fn reader(&self) -> ParameterReader
+    where
+        T: ParseParameter + Send + Sync + 'static,
+    {
+        let port = self.inner.clone();
+        Box::new(move || port.get_reference().map(|value| value.to_parameter_value()))
+    }
 }
 
 impl<T: Clone + Default + PortSerialization> Default for ParameterPort<T> {
     fn default() -> Self {
         let val = Arc::new(T::default());
         Self {
-            inner: Port { 
-                mode: Arc::new(RwLock::new(PortMode::Buffer(PortBuffer { buffer: Some(val.clone()) }))),
-            },
+            inner: Port::new(Some(val.clone())),
             buffer: val,
+            changed: false,
+            limits: None,
+            info: None,
+        }
+    }
+}
+
+impl<T: PortSerialization> PortField for SendPort<T> {
+    fn update_port(&mut self) {}
+
+    fn port_links(&self, name: &str, links: &mut Vec<PortLink>) {
+        links.push(self.link(name, PortDirection::Output));
+    }
+
+    fn serialize_port(&self, name: &str, port_data: &mut Vec<(String, PortDirection, PortData)>) {
+        if let Some(data) = self.get_arc() {
+            port_data.push((name.to_string(), PortDirection::Output, data.serialize_port_data()));
         }
     }
 }
 
+impl<T: PortSerialization> PortField for ReceivePort<T> {
+    fn sync_groups(&self, groups: &mut Vec<SyncPorts>) {
+        groups.extend(self.sync_group());
+    }
+
+    fn watch_ports(&self, trigger: &ChangeTrigger) {
+        trigger.watch(self);
+    }
+
+    fn update_port(&mut self) {
+        self.update();
+    }
+
+    fn port_links(&self, name: &str, links: &mut Vec<PortLink>) {
+        links.push(self.link(name, PortDirection::Input));
+    }
+
+    fn serialize_port(&self, name: &str, port_data: &mut Vec<(String, PortDirection, PortData)>) {
+        if let Some(data) = self.get_reference() {
+            port_data.push((name.to_string(), PortDirection::Input, data.serialize_port_data()));
+        }
+    }
+}
+
+impl<T: Default + PortSerialization> PortField for ParameterPort<T> {
+    fn watch_ports(&self, trigger: &ChangeTrigger) {
+        trigger.watch(&self.inner);
+    }
+
+    fn update_port(&mut self) {
+        self.update();
+    }
+
+    fn serialize_port(&self, name: &str, port_data: &mut Vec<(String, PortDirection, PortData)>) {
+        port_data.push((name.to_string(), PortDirection::Parameter, self.get_reference().serialize_port_data()));
+    }
+}
+
+impl<P: PortField> PortField for Option<P> {
+    fn sync_groups(&self, groups: &mut Vec<SyncPorts>) {
+        if let Some(port) = self {
+            port.sync_groups(groups);
+        }
+    }
+
+    fn watch_ports(&self, trigger: &ChangeTrigger) {
+        if let Some(port) = self {
+            port.watch_ports(trigger);
+        }
+    }
+
+    fn update_port(&mut self) {
+        if let Some(port) = self {
+            port.update_port();
+        }
+    }
+
+    fn port_links(&self, name: &str, links: &mut Vec<PortLink>) {
+        if let Some(port) = self {
+            port.port_links(name, links);
+        }
+    }
+
+    fn serialize_port(&self, name: &str, port_data: &mut Vec<(String, PortDirection, PortData)>) {
+        if let Some(port) = self {
+            port.serialize_port(name, port_data);
+        }
+    }
+}
+
+impl<P: PortField> PortField for Vec<P> {
+    fn sync_groups(&self, groups: &mut Vec<SyncPorts>) {
+        self.iter().for_each(|port| port.sync_groups(groups));
+    }
+
+    fn watch_ports(&self, trigger: &ChangeTrigger) {
+        self.iter().for_each(|port| port.watch_ports(trigger));
+    }
+
+    fn update_port(&mut self) {
+        self.iter_mut().for_each(PortField::update_port);
+    }
+
+    fn port_links(&self, name: &str, links: &mut Vec<PortLink>) {
+        for (index, port) in self.iter().enumerate() {
+            port.port_links(&format!("{}[{}]", name, index), links);
+        }
+    }
+
+    fn serialize_port(&self, name: &str, port_data: &mut Vec<(String, PortDirection, PortData)>) {
+        for (index, port) in self.iter().enumerate() {
+            port.serialize_port(&format!("{}[{}]", name, index), port_data);
+        }
+    }
+}
+
+impl<P: PortField, const N: usize> PortField for [P; N] {
+    fn sync_groups(&self, groups: &mut Vec<SyncPorts>) {
+        self.iter().for_each(|port| port.sync_groups(groups));
+    }
+
+    fn watch_ports(&self, trigger: &ChangeTrigger) {
+        self.iter().for_each(|port| port.watch_ports(trigger));
+    }
+
+    fn update_port(&mut self) {
+        self.iter_mut().for_each(PortField::update_port);
+    }
+
+    fn port_links(&self, name: &str, links: &mut Vec<PortLink>) {
+        for (index, port) in self.iter().enumerate() {
+            port.port_links(&format!("{}[{}]", name, index), links);
+        }
+    }
+
+    fn serialize_port(&self, name: &str, port_data: &mut Vec<(String, PortDirection, PortData)>) {
+        for (index, port) in self.iter().enumerate() {
+            port.serialize_port(&format!("{}[{}]", name, index), port_data);
+        }
+    }
+}
+
+/// Creates a `Vec` of `count` independent ports. 
+/// Use this instead of `vec![port; count]`, which would create clones of one port sharing the same data.
+pub fn port_vec<P: Default>(count: usize) -> Vec<P> {
+    (0..count).map(|_| P::default()).collect()
+}
+
+/// Connects collections of ports at once. Implemented for slices, `Vec`s and arrays of [`SendPort`]s and [`ReceivePort`]s.
+pub trait ConnectPorts<T: PortSerialization> {
+    /// Connects every port to the source with the same index. 
+    /// No port is connected if the number of sources does not match the number of ports.
+    fn connect_to_sources<'a, S>(&self, sources: impl IntoIterator<Item = &'a S>) -> Result<(), ConnectionError>
+    where
+        S: Deref<Target = Port<T>> + 'a;
+
+    /// Connects every port to the same source.
+    fn connect_all_to_source(&self, source: &Port<T>) -> Result<(), ConnectionError>;
+}
+
+macro_rules! ImplConnectPorts {
+    ($port:ident) => {
+        impl<T: PortSerialization> ConnectPorts<T> for [$port<T>] {
+            fn connect_to_sources<'a, S>(&self, sources: impl IntoIterator<Item = &'a S>) -> Result<(), ConnectionError>
+            where
+                S: Deref<Target = Port<T>> + 'a,
+            {
+                let sources: Vec<_> = sources.into_iter().collect();
+                if self.len() != sources.len() {
+                    return Err(ConnectionError::LengthMismatch { ports: self.len(), sources: sources.len() });
+                }
+                for (port, source) in self.iter().zip(sources) {
+                    port.connect_to_source(source)?;
+                }
+                Ok(())
+            }
+
+            fn connect_all_to_source(&self, source: &Port<T>) -> Result<(), ConnectionError> {
+                for port in self {
+                    port.connect_to_source(source)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+ImplConnectPorts!(SendPort);
+ImplConnectPorts!(ReceivePort);
+
 /// Used as outputs of the controll system to read data from modules and groups 
 /// from outside the system;
 pub struct OutputPort<T: Clone + PortSerialization> {
@@ -344,6 +1040,14 @@ impl<T: Clone + PortSerialization> InputPort<T> {
     }
 }
 
+impl<T: Clone + PortSerialization> Deref for InputPort<T> {
+    type Target = Port<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.target
+    }
+}
+
 impl<T: Clone + PortSerialization> From<&ReceivePort<T>> for InputPort<T> {
     fn from(port: &ReceivePort<T>) -> Self {
         Self {
@@ -355,13 +1059,153 @@ impl<T: Clone + PortSerialization> From<&ReceivePort<T>> for InputPort<T> {
 #[cfg(test)]
 mod tests { 
     use super::*;
+    use data_types::si_units::Distance;
+    use ib2c_macros::PortSerialization;
+    use crate::prelude::{ports, MetaSignals, UpdateReceivePorts, PortLinks, SwapPorts};
+
+    #[derive(Clone, PortSerialization)]
+    struct Obstacle {
+        distance: Distance,
+        confirmed: bool,
+    }
+
+    #[derive(Clone, PortSerialization)]
+    struct Pair<T>(T, T);
+
+    #[derive(Clone, PortSerialization)]
+    enum Detection {
+        Nothing,
+        Single(Obstacle),
+        Multiple { count: u32, nearest: Pair<i32> },
+    }
+
+    #[ports]
+    struct DistanceReader {
+        in_left: ReceivePort<u32>,
+        in_right: ReceivePort<u32>,
+    }
+
+    #[test]
+    fn test_sync_ports() {
+        let out_left: SendPort<u32> = SendPort::default();
+        let out_right: SendPort<u32> = SendPort::default();
+        let sensors = SyncPorts::new();
+        sensors.add(&out_left);
+        sensors.add(&out_right);
+
+        let mut reader = DistanceReader::default();
+        reader.in_left.connect_to_source(&out_left).unwrap();
+        reader.in_right.connect_to_source(&out_right).unwrap();
+        assert!(reader.in_left.sync_group().is_some());
+
+        let producer = std::thread::spawn(move || {
+            for measurement in 0..20_000 {
+                sensors.publish(|| {
+                    out_left.send(measurement);
+                    std::hint::spin_loop();
+                    out_right.send(measurement);
+                });
+            }
+        });
+        while !producer.is_finished() {
+            reader.update_all_ports();
+            assert_eq!(reader.in_left.get(), reader.in_right.get());
+        }
+        producer.join().unwrap();
+    }
+
+    #[test]
+    fn test_connect_port_collections() {
+        let sources: [SendPort<i32>; 3] = Default::default();
+        let mut targets: Vec<ReceivePort<i32>> = port_vec(3);
+        let mut fan_out: Vec<ReceivePort<i32>> = port_vec(2);
+
+        targets.connect_to_sources(&sources).unwrap();
+        fan_out.connect_all_to_source(&sources[0]).unwrap();
+        for (value, source) in sources.iter().enumerate() {
+            source.send(value as i32);
+        }
+        targets.update_port();
+        fan_out.update_port();
+
+        assert_eq!(targets.iter().map(|port| port.get()).collect::<Vec<_>>(), vec![Some(0), Some(1), Some(2)]);
+        assert_eq!(fan_out.iter().map(|port| port.get()).collect::<Vec<_>>(), vec![Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn test_derive_port_serialization() {
+        let obstacle = Obstacle { distance: Distance::meters(2.0), confirmed: true };
+        let PortData::Struct { fields } = obstacle.serialize_port_data() else {
+            panic!("expected struct");
+        };
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].0, "distance");
+        assert!(matches!(fields[0].1, PortData::SiValue { value: 2.0, .. }));
+        assert!(matches!(fields[1].1, PortData::Bool(true)));
+
+        let PortData::Struct { fields } = Pair(1, 2).serialize_port_data() else {
+            panic!("expected struct");
+        };
+        assert_eq!(fields[1].0, "1");
+        assert!(matches!(fields[1].1, PortData::Int(2)));
+
+        assert!(matches!(
+            Detection::Nothing.serialize_port_data(),
+            PortData::Enum { variant, payload: None } if variant == "Nothing"
+        ));
+        assert!(matches!(
+            Detection::Single(obstacle).serialize_port_data(),
+            PortData::Enum { variant, payload: Some(payload) } if variant == "Single" && matches!(*payload, PortData::Struct { .. })
+        ));
+        let detection = Detection::Multiple { count: 3, nearest: Pair(4, 5) };
+        assert_eq!(detection.serialize_port_data().to_string(), "Multiple({ count: 3, nearest: { 0: 4, 1: 5 } })");
+    }
+
+    #[test]
+    fn test_structured_port_data() {
+        assert_eq!(vec![1, 2, 3].serialize_port_data().to_string(), "[1, 2, 3]");
+        assert_eq!([true, false].serialize_port_data().to_string(), "[true, false]");
+        assert_eq!((1, "a".to_string()).serialize_port_data().to_string(), "[1, a]");
+        assert!(matches!(None::<i32>.serialize_port_data(), PortData::Optional(None)));
+        assert!(matches!(Some(4).serialize_port_data(), PortData::Optional(Some(data)) if matches!(*data, PortData::Int(4))));
+
+        let position = Vector::from([Distance::meters(1.0), Distance::meters(2.0)]);
+        let PortData::Vector { components, unit } = position.serialize_port_data() else {
+            panic!("expected vector");
+        };
+        assert_eq!(components, vec![1.0, 2.0]);
+        assert_eq!(unit, Distance::ZERO.unit_str());
+
+        let obstacles = Vector::from([Some(1), None]);
+        assert!(matches!(obstacles.serialize_port_data(), PortData::List(items) if items.len() == 2));
+    }
+
+    #[test]
+    fn test_rotation_port_data() {
+        let rotation = Rotation2D::PI_2;
+        assert!(matches!(rotation.serialize_port_data(), PortData::SiValue { value, .. } if (value - 90.0).abs() < 1e-9));
+
+        let send_port: SendPort<Rotation3D> = SendPort::default();
+        let mut receive_port: ReceivePort<Rotation3D> = ReceivePort::default();
+        receive_port.connect_to_source(&send_port).unwrap();
+        send_port.send(Rotation3D::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2));
+        receive_port.update();
+
+        let PortData::Struct { fields } = receive_port.get_or_default().serialize_port_data() else {
+            panic!("expected struct");
+        };
+        let PortData::Vector { components, .. } = &fields[1].1 else {
+            panic!("expected vector");
+        };
+        assert!((components[2] - 90.0).abs() < 1e-9);
+    }
 
     #[test]
     fn test_send_receive_port() {
         let send_port: SendPort<i32> = SendPort::default();
         let mut receive_port: ReceivePort<i32> = ReceivePort::default();
 
-        receive_port.connect_to_source(&send_port);
+        receive_port.connect_to_source(&send_port).unwrap();
 
 
         assert_eq!(receive_port.get(), None);
@@ -376,8 +1220,8 @@ mod tests {
         let mut receive_port1: ReceivePort<i32> = ReceivePort::default();
         let mut receive_port2: ReceivePort<i32> = ReceivePort::default();
 
-        receive_port1.connect_to_source(&send_port);
-        receive_port2.connect_to_source(&receive_port1);
+        receive_port1.connect_to_source(&send_port).unwrap();
+        receive_port2.connect_to_source(&receive_port1).unwrap();
 
         send_port.send(42);
         receive_port1.update();
@@ -393,8 +1237,8 @@ mod tests {
         let send_port2: SendPort<i32> = SendPort::default();
         let mut receive_port: ReceivePort<i32> = ReceivePort::default();
 
-        send_port2.connect_to_source(&send_port1);
-        receive_port.connect_to_source(&send_port2);
+        send_port2.connect_to_source(&send_port1).unwrap();
+        receive_port.connect_to_source(&send_port2).unwrap();
 
         send_port1.send(42);
         receive_port.update();
@@ -411,11 +1255,11 @@ mod tests {
         let mut receive_port2: ReceivePort<i32> = ReceivePort::default();
         let mut receive_port3: ReceivePort<i32> = ReceivePort::default();
 
-        send_port1.connect_as_source(&send_port2);
-        receive_port1.connect_to_source(&send_port2);
-        send_port3.connect_to_source(&receive_port1);
-        receive_port2.connect_to_source(&send_port3);
-        receive_port2.connect_as_source(&receive_port3);
+        send_port1.connect_as_source(&send_port2).unwrap();
+        receive_port1.connect_to_source(&send_port2).unwrap();
+        send_port3.connect_to_source(&receive_port1).unwrap();
+        receive_port2.connect_to_source(&send_port3).unwrap();
+        receive_port2.connect_as_source(&receive_port3).unwrap();
         send_port1.send(42);
 
         receive_port1.update();
@@ -429,4 +1273,74 @@ mod tests {
         assert_eq!(send_port1.get(), Some(42));
     }
 
+    #[test]
+    fn test_reconnect_chain() {
+        let send_port1: SendPort<i32> = SendPort::default();
+        let send_port2: SendPort<i32> = SendPort::default();
+        let boundary_port: ReceivePort<i32> = ReceivePort::default();
+        let mut receive_port: ReceivePort<i32> = ReceivePort::default();
+
+        receive_port.connect_to_source(&boundary_port).unwrap();
+        boundary_port.connect_to_source(&send_port1).unwrap();
+        send_port1.send(1);
+        receive_port.update();
+        assert_eq!(receive_port.get(), Some(1));
+
+        boundary_port.disconnect();
+        receive_port.update();
+        assert_eq!(receive_port.get(), None);
+
+        boundary_port.connect_to_source(&send_port2).unwrap();
+        send_port2.send(2);
+        receive_port.update();
+        assert_eq!(receive_port.get(), Some(2));
+    }
+
+    #[test]
+    fn test_connection_errors() {
+        let send_port: SendPort<i32> = SendPort::default();
+        let other_send_port: SendPort<i32> = SendPort::default();
+        let receive_port1: ReceivePort<i32> = ReceivePort::default();
+        let mut receive_port2: ReceivePort<i32> = ReceivePort::default();
+
+        assert_eq!(receive_port1.connect_to_source(&receive_port1), Err(ConnectionError::Cycle));
+        receive_port1.connect_to_source(&send_port).unwrap();
+        receive_port2.connect_to_source(&receive_port1).unwrap();
+        assert_eq!(send_port.connect_to_source(&receive_port2), Err(ConnectionError::Cycle));
+        assert_eq!(receive_port1.connect_to_source(&other_send_port), Err(ConnectionError::AlreadyConnected));
+
+        other_send_port.send(1);
+        assert_eq!(other_send_port.connect_to_source(&send_port), Err(ConnectionError::AlreadySent));
+
+        let ports: Vec<ReceivePort<i32>> = port_vec(2);
+        assert_eq!(ports.connect_to_sources([&send_port]), Err(ConnectionError::LengthMismatch { ports: 2, sources: 1 }));
+        assert!(ports.connect_to_sources(&[send_port.clone(), other_send_port.clone()]).is_ok());
+
+        receive_port1.disconnect();
+        receive_port1.connect_to_source(&other_send_port).unwrap();
+        send_port.send(2);
+        receive_port2.update();
+        assert_eq!(receive_port2.get(), Some(1));
+    }
+
+    #[test]
+    fn test_concurrent_cycle() {
+        // connecting two ports to each other from two threads at once must not create a cycle
+        for _ in 0..100 {
+            let port1: ReceivePort<i32> = ReceivePort::default();
+            let port2: ReceivePort<i32> = ReceivePort::default();
+            let barrier = std::sync::Barrier::new(2);
+            let results = std::thread::scope(|scope| {
+                let first = scope.spawn(|| {
+                    barrier.wait();
+                    port1.connect_to_source(&port2)
+                });
+                barrier.wait();
+                let second = port2.connect_to_source(&port1);
+                [first.join().unwrap(), second]
+            });
+            assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+            assert!(results.contains(&Err(ConnectionError::Cycle)));
+        }
+    }
 }
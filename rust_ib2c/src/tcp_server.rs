@@ -1,12 +1,98 @@
-use std::{io::Write, net::TcpListener, sync::{Arc, Mutex}, time::Duration};
+use std::{io::{Read, Write}, net::{TcpListener, TcpStream}, path::{Component, Path}, sync::{atomic::{AtomicBool, Ordering}, Arc, Condvar, Mutex}, time::Duration};
 
-use rust_ib2c_shared_data::SharedData;
+use rust_ib2c_shared_data::{MonitorCommand, SharedData};
+
+use crate::{fault::Supervisor, network::NetworkRegistry, parameters::{ParameterRegistry, ParameterSet}, traits::Spawnable};
+
+/// Maximum size of a serialized command of the monitor. Connections sending larger commands are closed.
+const MAX_COMMAND_SIZE: usize = 64 * 1024;
 
 
 #[derive(Default)]
 pub struct Parent {
     pub path: String,
     pub tcp_server: TcpServer,
+    pub(crate) stop: Arc<AtomicBool>,
+    pub(crate) supervisor: Option<Arc<Supervisor>>,
+    pub(crate) parameters: Option<Arc<ParameterSet>>,
+    pub(crate) live_parameters: Arc<ParameterRegistry>,
+    pub(crate) network: Arc<NetworkRegistry>,
+    pub(crate) pending_spawns: Mutex<Vec<PendingSpawn>>,
+    pub(crate) start_gate: StartGate,
+}
+
+/// Module registered with [`Parent::spawn`] that is not started yet.
+type PendingSpawn = Box<dyn FnOnce() + Send>;
+
+impl Parent {
+    /// Creates the [`Parent`] of a module or group with the given name spawned from this parent.
+    pub fn child(&self, name: &str) -> Parent {
+        Parent {
+            path: format!("{}/{}", self.path, name),
+            tcp_server: self.tcp_server.clone(),
+            stop: Arc::clone(&self.stop),
+            supervisor: self.supervisor.clone(),
+            parameters: self.parameters.clone(),
+            live_parameters: Arc::clone(&self.live_parameters),
+            network: Arc::clone(&self.network),
+            pending_spawns: Mutex::default(),
+            start_gate: self.start_gate.clone(),
+        }
+    }
+
+    /// Registers a module that is started once the [`Group::init`][crate::traits::Group::init] this parent was passed to returns.
+    /// Use this for modules created in loops or helper functions, which are not found by the [`spawn`][crate::prelude::spawn] attribute.
+    /// Modules have to be connected before they are registered.
+    pub fn spawn(&self, module: impl Spawnable) {
+        self.pending_spawns.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(Box::new(move || module.spawn()));
+    }
+
+    /// Starts all modules registered with [`Parent::spawn`].
+    pub(crate) fn spawn_pending(&self) {
+        let pending_spawns = std::mem::take(&mut *self.pending_spawns.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+        for spawn in pending_spawns {
+            spawn();
+        }
+    }
+
+    /// Requests all modules spawned from the same main group to stop.
+    pub fn request_stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true once a stop of the system was requested.
+    pub fn stop_requested(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+}
+
+/// Holds back the threads of spawned modules until the main group was initialized and its parameters were checked.
+/// Open unless created with [`StartGate::closed`].
+#[derive(Clone, Default)]
+pub(crate) struct StartGate {
+    closed: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl StartGate {
+    pub(crate) fn closed() -> Self {
+        Self {
+            closed: Arc::new((Mutex::new(true), Condvar::new())),
+        }
+    }
+
+    pub(crate) fn open(&self) {
+        let (closed, condvar) = &*self.closed;
+        *closed.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = false;
+        condvar.notify_all();
+    }
+
+    /// Blocks until the gate is opened.
+    pub(crate) fn wait(&self) {
+        let (closed, condvar) = &*self.closed;
+        let closed = closed.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _open = condvar.wait_while(closed, |closed| *closed).unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
 }
 
 #[derive(Default)]
@@ -27,18 +113,18 @@ impl TcpServer {
         }
     }
 
-    pub fn start(&self) {
+    /// Starts the server thread. Commands of the monitor are executed on the parameters of the system.
+    pub(crate) fn start(&self, live_parameters: Arc<ParameterRegistry>) {
         // Spawn TCP server thread here
         let buffer = Arc::clone(&self.buffer);
         std::thread::spawn(move || {
             let tcp_socket = TcpListener::bind("127.0.0.1:13337").unwrap();
             tcp_socket.set_ttl(Duration::from_secs(1).as_secs() as u32).unwrap();
             println!("TCP Server listening on port 13337");
-            let Ok((mut connection, _)) = tcp_socket.accept() else {
+            let Ok(mut connection) = accept_connection(&tcp_socket, &live_parameters) else {
                 println!("Failed to accept connection");
                 return;
             };
-            println!("Client connected: {:?}", connection);
             loop {
                 let data = buffer.lock().unwrap().take();
                 match data {
@@ -48,15 +134,13 @@ impl TcpServer {
                         if let Err(e) = connection.write_all(&length) {
                             println!("Connection error: {}", e);
                             println!("Searching for new connection...");
-                            connection = tcp_socket.accept().unwrap().0;
-                            println!("Client connected: {:?}", connection);
+                            connection = accept_connection(&tcp_socket, &live_parameters).unwrap();
                             continue;
                         }
                         if let Err(e) = connection.write_all(&serialized) {
                             println!("Connection error: {}", e);
                             println!("Searching for new connection...");
-                            connection = tcp_socket.accept().unwrap().0;
-                            println!("Client connected: {:?}", connection);
+                            connection = accept_connection(&tcp_socket, &live_parameters).unwrap();
                             continue;
                         }
                     }
@@ -69,6 +153,52 @@ impl TcpServer {
     }
 }
 
+/// Accepts a monitor connection and starts a thread receiving its commands.
+fn accept_connection(tcp_socket: &TcpListener, live_parameters: &Arc<ParameterRegistry>) -> std::io::Result<TcpStream> {
+    let (connection, _) = tcp_socket.accept()?;
+    println!("Client connected: {:?}", connection);
+    let commands = connection.try_clone()?;
+    let live_parameters = Arc::clone(live_parameters);
+    std::thread::spawn(move || receive_commands(commands, &live_parameters));
+    Ok(connection)
+}
+
+/// Executes commands of the monitor until the connection is closed or a command exceeds [`MAX_COMMAND_SIZE`].
+fn receive_commands(mut connection: impl Read, live_parameters: &ParameterRegistry) {
+    loop {
+        let mut length = [0u8; 4];
+        if connection.read_exact(&mut length).is_err() {
+            return;
+        }
+        let length = u32::from_be_bytes(length) as usize;
+        if length > MAX_COMMAND_SIZE {
+            println!("Monitor command of {} bytes exceeds the maximum of {} bytes, closing the connection", length, MAX_COMMAND_SIZE);
+            return;
+        }
+        let mut data = vec![0u8; length];
+        if connection.read_exact(&mut data).is_err() {
+            return;
+        }
+        match serde_json::from_slice(&data) {
+            Ok(MonitorCommand::SaveParameters { path }) if !is_monitor_save_path(&path) => {
+                println!("Refused to save parameters to {}, only paths inside the working directory are allowed", path);
+            }
+            Ok(MonitorCommand::SaveParameters { path }) => match live_parameters.snapshot().save(&path) {
+                Ok(()) => println!("Saved parameters to {}", path),
+                Err(error) => println!("Failed to save parameters: {}", error),
+            },
+            Err(error) => println!("Invalid monitor command: {}", error),
+        }
+    }
+}
+
+/// Monitors may only save parameter files inside the working directory of the system,
+/// so absolute paths and paths leaving the directory with `..` are refused.
+fn is_monitor_save_path(path: &str) -> bool {
+    let mut components = Path::new(path).components().peekable();
+    components.peek().is_some() && components.all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
 impl Clone for TcpServer {
     fn clone(&self) -> Self {
         TcpServer {
@@ -77,3 +207,32 @@ impl Clone for TcpServer {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::parameters::ParameterRegistry;
+
+    use super::{is_monitor_save_path, receive_commands, MAX_COMMAND_SIZE};
+
+    #[test]
+    fn test_monitor_save_path() {
+        assert!(is_monitor_save_path("parameters.toml"));
+        assert!(is_monitor_save_path("./config/parameters.yaml"));
+        assert!(!is_monitor_save_path(""));
+        assert!(!is_monitor_save_path("/etc/parameters.toml"));
+        assert!(!is_monitor_save_path("../parameters.toml"));
+        assert!(!is_monitor_save_path("config/../../parameters.toml"));
+    }
+
+    #[test]
+    fn test_oversized_command() {
+        let mut stream = (MAX_COMMAND_SIZE as u32 + 1).to_be_bytes().to_vec();
+        stream.extend(vec![b' '; MAX_COMMAND_SIZE + 1]);
+        let mut connection = Cursor::new(stream);
+        receive_commands(&mut connection, &ParameterRegistry::default());
+        // the connection is dropped after the length of the command, its data is not read
+        assert_eq!(connection.position(), 4);
+    }
+}
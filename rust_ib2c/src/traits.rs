@@ -1,18 +1,23 @@
 use std::time::Duration;
-use rust_ib2c_shared_data::PortData;
+use rust_ib2c_shared_data::{PortData, PortDirection};
 
 use crate::{prelude::*, tcp_server::Parent};
 
 /// Module trait for behavior modules. Can be spawned using the [`BehaviorModule`] struct.
-pub trait Module: UpdateReceivePorts + MetaSignals + PortParsing + Default {
+pub trait Module: UpdateReceivePorts + MetaSignals + PortParsing + PortLinks + SwapPorts + ModuleAttributes + ParameterLoading + Default {
+    /// Natural cycle time of the module. Used instead of the cycle time of the group
+    /// unless a cycle time is passed to `SpawnModule!`.
+    /// Defaults to the cycle time declared with `#[module(cycle_time = "20ms")]`.
+    const CYCLE_TIME: Option<Duration> = Self::DECLARED_CYCLE_TIME;
+
     /// Spawn other modules and groups here and connect them.
     /// 
     /// Use the SpawnModule!, SpawnGroup! and SpawnFusion! macros to create instances.
     /// # Examples
-    /// ```rust
+    /// ```rust ignore
     /// fn init(&mut self, cycle_time: Duration, parent: &Parent) {
     ///    let break_on_obstacle = SpawnModule!(BreakOnObstacle, "BreakOnObstacle");
-    ///    break_on_obstacle.in_distance.connect_to_source(&self.in_front_distance_sensor);
+    ///    break_on_obstacle.in_distance.connect_to_source(&self.in_front_distance_sensor).unwrap();
     ///
     ///    let constant_velocity = SpawnModule!(ConstantVelocity, "ConstantVelocity");
     ///    
@@ -25,22 +30,74 @@ pub trait Module: UpdateReceivePorts + MetaSignals + PortParsing + Default {
     ///        ]
     ///    };
     ///
-    ///    self.out_velocity.connect_to_source(&maximum_fusion.output);   
+    ///    self.out_velocity.connect_to_source(&maximum_fusion.output).unwrap();
     ///
-    ///    self.set_characteristic_module(&mut maximum_fusion);
+    ///    self.set_characteristic_module(&mut maximum_fusion).unwrap();
     ///}
     fn init() -> Self {
         Self::default()
     }
+    /// Computes the outputs of the module. Evaluate the inputs needed by the target rating in [`sense`][Module::sense]
+    /// to split the module into a bottom-up and a top-down part.
     fn transfere(&mut self);
     fn target_rating(&self) -> MetaSignal;
+
+    /// Bottom-up part of the module: evaluates the inputs needed by [`target_rating`][Module::target_rating].
+    /// Is always called before the target rating is read.
+    fn sense(&mut self) {}
+
+    /// Top-down part of the module: computes the outputs.
+    /// Depending on the [`ControlMode`] of the [`BehaviorModule`] it is only called while the module is active.
+    /// Defaults to calling [`transfere`][Module::transfere].
+    fn control(&mut self) {
+        self.transfere();
+    }
+
+    /// Fallible variant of [`control`][Module::control] called by the [`BehaviorModule`].
+    /// Returned errors are handled according to the [`FaultPolicy`] of the [`BehaviorModule`].
+    /// Defaults to calling [`control`][Module::control].
+    fn try_transfere(&mut self) -> Result<(), ModuleError> {
+        self.control();
+        Ok(())
+    }
+
+    /// Called once in the module thread before the first cycle.
+    fn on_start(&mut self) {}
+
+    /// Called after the ports were updated for every parameter that was set since the previous cycle,
+    /// e.g. to rebuild state derived from the parameter. Called for all parameters before the first cycle
+    /// and after a restart, so state derived from default values is built as well.
+    /// Changes made while the module is frozen are reported once it runs again.
+    fn on_parameter_changed(&mut self, _name: &str) {}
+
+    /// Called when the activity rises above the activation threshold of the [`BehaviorModule`].
+    fn on_activate(&mut self) {}
+
+    /// Called when the activity falls back to or below the activation threshold of the [`BehaviorModule`].
+    fn on_deactivate(&mut self) {}
+
+    /// Called once after the last cycle when the system is shut down.
+    fn on_stop(&mut self) {}
 }
 
 /// Module trait for groups of behavior modules. Can be spawned using the [`BehaviorGroup`] struct.
-pub trait Group: MetaSignals + UpdateReceivePorts + Default {
+pub trait Group: MetaSignals + UpdateReceivePorts + PortLinks + Members + Default {
+    /// Restart strategy for the modules spawned in this group.
+    /// Subgroups without their own strategy are supervised by the enclosing group.
+    const RESTART_STRATEGY: RestartStrategy = RestartStrategy::None;
+
+    /// Combines the activities and target ratings of the members added with `add_member` into the activity and target rating of the group.
+    const ACTIVITY_AGGREGATION: ActivityAggregation = ActivityAggregation::Maximum;
+
     fn init(&mut self, cycle_time: std::time::Duration, path: &Parent);
 }
 
+/// Modules that run in their own thread, like [`BehaviorModule`] and [`MaximumFusion`].
+/// Can be registered with [`Parent::spawn`] to be started after the [`Group::init`] of their group.
+pub trait Spawnable: Send + 'static {
+    fn spawn(self);
+}
+
 /// Trait for access to meta signals: activity, target rating, stimulation, inhibition
 pub trait MetaSignals {
     fn set_activity(&mut self, activity: MetaSignal);
@@ -58,12 +115,50 @@ pub trait MetaSignals {
 
 /// Internal trait to get all port data of a module for serialization 
 pub trait PortParsing {
-    fn all_port_data(&self) -> Vec<(&'static str, PortData)>;
+    fn all_port_data(&self) -> Vec<(String, PortDirection, PortData)>;
+}
+
+/// Trait for fields of modules and groups holding ports. 
+/// Implemented for all port types and for `Option`s, `Vec`s and arrays of ports.
+pub trait PortField {
+    /// Collects the [`SyncPorts`] groups of all contained receive ports.
+    fn sync_groups(&self, _groups: &mut Vec<SyncPorts>) {}
+    /// Lets the trigger watch all contained receive and parameter ports.
+    fn watch_ports(&self, _trigger: &ChangeTrigger) {}
+    /// Updates the buffers of all contained receive and parameter ports.
+    fn update_port(&mut self);
+    /// Serializes the data of all contained ports. Ports in collections are named `name[index]`.
+    fn serialize_port(&self, name: &str, port_data: &mut Vec<(String, PortDirection, PortData)>);
+    /// Collects links to all contained send and receive ports, named like in [`PortField::serialize_port`].
+    fn port_links(&self, _name: &str, _links: &mut Vec<PortLink>) {}
+}
+
+/// Options declared with the `#[module]` attribute. Implemented by the macro.
+pub trait ModuleAttributes {
+    const DECLARED_CYCLE_TIME: Option<Duration>;
 }
 
 /// Trait for updating all receive ports of modules and groups.
 pub trait UpdateReceivePorts {
     fn update_all_ports(&mut self);
+    /// Lets the trigger watch all receive and parameter ports, including stimulation and inhibition.
+    fn watch_receive_ports(&self, trigger: &ChangeTrigger);
+}
+
+/// Trait for listing all ports of modules and groups, including the meta signal ports.
+/// Used to export the structure of a system with [`BehaviorGroup::network`][crate::group::BehaviorGroup::network].
+pub trait PortLinks {
+    fn port_links(&self) -> Vec<PortLink>;
+}
+
+/// Access to the members of a group added with `add_member`. Implemented by the `#[group]` macro.
+pub trait Members {
+    fn members(&mut self) -> &mut GroupMembers;
+}
+
+/// Trait for exchanging all ports of a module with another instance, keeping their connections.
+pub trait SwapPorts {
+    fn swap_ports(&mut self, other: &mut Self);
 }
 
 /// Required for serialization of port data.
@@ -12,7 +12,51 @@ pub struct SharedData {
     pub stimulation: f32,
     pub inhibition: f32,
     pub source: String,
-    pub data: Vec<(String, PortData)>,
+    pub data: Vec<(String, PortDirection, PortData)>,
+    /// Last fault reported by the module, if any.
+    #[serde(default)]
+    pub fault: Option<String>,
+    /// Declared limits and display hints of the parameters of the module.
+    #[serde(default)]
+    pub parameters: Vec<(String, ParameterInfo)>,
+}
+
+/// Limits and display hints of a parameter declared with `#[parameter(...)]`.
+/// Limits and step size are serialized like the value of the parameter.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ParameterInfo {
+    pub min: Option<PortData>,
+    pub max: Option<PortData>,
+    pub step: Option<PortData>,
+    pub description: Option<String>,
+    pub unit: Option<DisplayUnit>,
+}
+
+/// Unit a parameter is displayed in.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DisplayUnit {
+    pub symbol: String,
+    /// Value of one display unit in the unit of the serialized port data, e.g. 0.01 for "cm" if the value is serialized in meters.
+    pub factor: f64,
+}
+
+/// Command sent from the monitor to the system, framed like [`SharedData`] with a big-endian length prefix.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum MonitorCommand {
+    /// Saves the current parameter values of all modules to a parameter file on the system.
+    /// The format is determined by the file extension (`.toml`, `.json`, `.yaml` or `.yml`).
+    /// The path has to be relative and inside the working directory of the system, other paths are refused.
+    SaveParameters {
+        path: String,
+    },
+}
+
+/// Direction of a port as seen from the module or group it belongs to.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortDirection {
+    Input,
+    Output,
+    Parameter,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -27,6 +71,51 @@ pub enum PortData {
         value: f64,
         unit: String,   
     },
+    /// Named or unnamed fields of a struct. Unnamed fields are named by their index.
+    Struct {
+        fields: Vec<(String, PortData)>,
+    },
+    /// Variant of an enum. Unit variants have no payload.
+    Enum {
+        variant: String,
+        payload: Option<Box<PortData>>,
+    },
+    /// Sequence of values, used for lists, arrays and tuples.
+    List(Vec<PortData>),
+    /// Vector of numeric components sharing one unit.
+    Vector {
+        components: Vec<f64>,
+        unit: String,
+    },
+    /// Optional value.
+    Optional(Option<Box<PortData>>),
+}
+
+impl PortData {
+    /// Returns the value of numeric port data.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            PortData::Float(v) | PortData::SiValue { value: v, .. } => Some(*v),
+            PortData::Int(v) => Some(*v as f64),
+            PortData::Unsigned(v) => Some(*v as f64),
+            PortData::MetaSignal(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the named children of structured port data. Scalar port data has no children.
+    pub fn children(&self) -> Vec<(String, PortData)> {
+        match self {
+            PortData::Struct { fields } => fields.clone(),
+            PortData::Enum { payload: Some(payload), .. } => payload.children(),
+            PortData::List(items) => items.iter().enumerate().map(|(index, item)| (format!("[{}]", index), item.clone())).collect(),
+            PortData::Vector { components, unit } => components.iter().enumerate().map(|(index, component)| {
+                (format!("[{}]", index), PortData::SiValue { value: *component, unit: unit.clone() })
+            }).collect(),
+            PortData::Optional(Some(data)) => data.children(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 impl Display for PortData {
@@ -39,6 +128,40 @@ impl Display for PortData {
             PortData::String(v) => write!(f, "{}", v),
             PortData::MetaSignal(v) => write!(f, "{:?}", v),
             PortData::SiValue { value, unit } => write!(f, "{:.4} {}", value, unit),
+            PortData::Struct { fields } => {
+                write!(f, "{{ ")?;
+                for (index, (name, data)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", name, data)?;
+                }
+                write!(f, " }}")
+            }
+            PortData::Enum { variant, payload: Some(payload) } => write!(f, "{}({})", variant, payload),
+            PortData::Enum { variant, payload: None } => write!(f, "{}", variant),
+            PortData::List(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            PortData::Vector { components, unit } => {
+                write!(f, "[")?;
+                for (index, component) in components.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:.4}", component)?;
+                }
+                write!(f, "] {}", unit)
+            }
+            PortData::Optional(Some(data)) => write!(f, "{}", data),
+            PortData::Optional(None) => write!(f, "None"),
         }
     }
 }
\ No newline at end of file
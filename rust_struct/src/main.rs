@@ -1,15 +1,18 @@
-use std::collections::HashMap;
-use std::io::Read;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
 use std::net::TcpStream;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use iced::border::Radius;
 use iced::widget::container::Style;
-use iced::widget::{column, row, scrollable, text};
+use iced::widget::{button, column, progress_bar, row, scrollable, text, Column};
 use iced::{Border, Element, Length, Subscription, Task};
 
-use rust_ib2c_shared_data::SharedData;
+use rust_ib2c_shared_data::{MonitorCommand, ParameterInfo, PortData, PortDirection, SharedData};
+
+/// Parameter file written by the system when the parameters are saved, relative to its working directory.
+const PARAMETER_FILE: &str = "parameters.toml";
 
 pub fn main() -> iced::Result {
     iced::application("Ruststruct", update, view)
@@ -58,17 +61,30 @@ async fn fetch_data(stream: Arc<Mutex<Option<TcpStream>>>) -> Option<Vec<SharedD
     Some(result)
 }
 
+async fn send_command(stream: Arc<Mutex<Option<TcpStream>>>, command: MonitorCommand) -> Result<(), String> {
+    let mut tcp_stream = stream.lock().map_err(|e| e.to_string())?;
+    let stream = tcp_stream.as_mut().ok_or("Not connected")?;
+    let serialized = serde_json::to_vec(&command).map_err(|e| e.to_string())?;
+    let length = (serialized.len() as u32).to_be_bytes();
+    stream.write_all(&length).map_err(|e| e.to_string())?;
+    stream.write_all(&serialized).map_err(|e| e.to_string())
+}
 
 #[derive(Debug, Clone)]
 enum Message {
     FetchData,
     DataReceived(Option<Vec<SharedData>>),
+    TogglePort(String),
+    SaveParameters,
+    CommandSent(Result<(), String>),
 }
 
 #[derive(Default)]
 struct State {
     tcp_stream: Arc<Mutex<Option<TcpStream>>>,
     module_data: HashMap<String, SharedData>,
+    expanded_ports: HashSet<String>,
+    status: Option<String>,
 }
 
 fn update(state: &mut State, message: Message) -> Task<Message> {
@@ -87,6 +103,21 @@ fn update(state: &mut State, message: Message) -> Task<Message> {
                 state.module_data.clear();
             }
         }
+        Message::TogglePort(key) => {
+            if !state.expanded_ports.remove(&key) {
+                state.expanded_ports.insert(key);
+            }
+        }
+        Message::SaveParameters => {
+            let command = MonitorCommand::SaveParameters { path: PARAMETER_FILE.to_string() };
+            return Task::perform(send_command(state.tcp_stream.clone(), command), Message::CommandSent)
+        }
+        Message::CommandSent(result) => {
+            state.status = Some(match result {
+                Ok(()) => format!("Requested saving the parameters to {}", PARAMETER_FILE),
+                Err(e) => format!("Failed to request saving the parameters: {}", e),
+            });
+        }
         
     }
     Task::none()
@@ -94,12 +125,19 @@ fn update(state: &mut State, message: Message) -> Task<Message> {
 
 fn view(state: &'_ State) -> Element<'_, Message> {
     let mut col = column![
-        text("Module Data").size(40),
+        row![
+            text("Module Data").size(40).width(Length::Fill),
+            button(text("Save parameters")).on_press(Message::SaveParameters),
+        ].align_y(iced::Alignment::Center),
     ]
     .width(iced::Length::Fill)
     .padding(20)
     .spacing(10);
 
+    if let Some(status) = &state.status {
+        col = col.push(text(status));
+    }
+
     if state.module_data.is_empty() {
         col = col.push(text("No data received yet...").size(30));
         return scrollable(col).into();
@@ -126,15 +164,28 @@ fn view(state: &'_ State) -> Element<'_, Message> {
         inner_col = inner_col.push(row![
             text("Inhibition:").width(Length::Fixed(200.0)), text(format!("{:.2}", data.inhibition)),
         ]);
-        outer_row = outer_row.push(inner_col);
-        let mut inner_col = column![].width(Length::FillPortion(1));
-        inner_col = inner_col.push(text("Port Data:").size(20));
-        for (port_name, port_data) in data.data.iter() {
+        if let Some(fault) = &data.fault {
             inner_col = inner_col.push(row![
-                text(port_name).width(Length::Fixed(200.0)), text(format!("{}", port_data)),
+                text("Fault:").width(Length::Fixed(200.0)), text(fault).color(iced::Color::from_rgb(1.0, 0.3, 0.3)),
             ]);
         }
         outer_row = outer_row.push(inner_col);
+        let mut inner_col = column![].width(Length::FillPortion(1));
+        for (title, direction) in [("Inputs:", PortDirection::Input), ("Outputs:", PortDirection::Output), ("Parameters:", PortDirection::Parameter)] {
+            let mut ports = data.data.iter().filter(|(_, port_direction, _)| *port_direction == direction).peekable();
+            if ports.peek().is_none() {
+                continue;
+            }
+            inner_col = inner_col.push(text(title).size(20));
+            for (port_name, _, port_data) in ports {
+                let info = data.parameters.iter().find(|(name, _)| name == port_name).map(|(_, info)| info);
+                inner_col = match info {
+                    Some(info) => push_parameter(inner_col, port_name, port_data, info),
+                    None => push_port_data(inner_col, format!("{}/{}", key, port_name), port_name, port_data, 0, &state.expanded_ports),
+                };
+            }
+        }
+        outer_row = outer_row.push(inner_col);
         outer_col = outer_col.push(outer_row);
 
 
@@ -154,4 +205,66 @@ fn view(state: &'_ State) -> Element<'_, Message> {
     }
 
     scrollable(col).into()
+}
+
+/// Pushes a row for a parameter with declared limits, showing its value in the declared unit and its position within the limits.
+fn push_parameter<'a>(mut col: Column<'a, Message>, name: &str, data: &PortData, info: &ParameterInfo) -> Column<'a, Message> {
+    let format_value = |data: &PortData| match (&info.unit, data.as_f64()) {
+        (Some(unit), Some(value)) => format!("{:.4} [{}]", value / unit.factor, unit.symbol),
+        _ => format!("{}", data),
+    };
+    col = col.push(row![
+        text(name.to_string()).width(Length::Fixed(200.0)), text(format_value(data)),
+    ]);
+    let limits = info.min.as_ref().zip(info.max.as_ref());
+    if let Some((min, max)) = limits
+        && let (Some(min_value), Some(max_value), Some(value)) = (min.as_f64(), max.as_f64(), data.as_f64())
+        && min_value < max_value
+    {
+        col = col.push(row![
+            text(format_value(min)).width(Length::Fixed(200.0)),
+            progress_bar(min_value as f32..=max_value as f32, value as f32).width(Length::Fixed(200.0)).height(Length::Fixed(10.0)),
+            text(format_value(max)),
+        ].spacing(10).align_y(iced::Alignment::Center));
+    }
+    if let Some(description) = &info.description {
+        col = col.push(text(description.clone()).size(12));
+    }
+    col
+}
+
+/// Pushes a row for the port data and, if expanded, rows for all of its children.
+fn push_port_data<'a>(
+    mut col: Column<'a, Message>, 
+    key: String, 
+    name: &str, 
+    data: &PortData, 
+    depth: usize, 
+    expanded_ports: &HashSet<String>,
+) -> Column<'a, Message> {
+    let indent = 20.0 * depth as f32;
+    let children = data.children();
+    if children.is_empty() {
+        return col.push(row![
+            text(name.to_string()).width(Length::Fixed(200.0 - indent)), text(format!("{}", data)),
+        ].padding(iced::Padding::ZERO.left(indent)));
+    }
+
+    let expanded = expanded_ports.contains(&key);
+    let marker = if expanded { "▾" } else { "▸" };
+    col = col.push(row![
+        button(text(format!("{} {}", marker, name)))
+            .on_press(Message::TogglePort(key.clone()))
+            .style(button::text)
+            .padding(0)
+            .width(Length::Fixed(200.0 - indent)),
+        text(format!("{}", data)),
+    ].padding(iced::Padding::ZERO.left(indent)));
+
+    if expanded {
+        for (child_name, child_data) in &children {
+            col = push_port_data(col, format!("{}/{}", key, child_name), child_name, child_data, depth + 1, expanded_ports);
+        }
+    }
+    col
 }
\ No newline at end of file
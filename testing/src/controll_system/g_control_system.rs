@@ -17,15 +17,15 @@ impl Group for ControlSystem {
     #[spawn]
     fn init(&mut self, cycle_time: Duration, parent: &Parent) {
         let mut velocity_control = SpawnGroup!(VelocityControl, "VelocityControl");
-        velocity_control.in_front_distance_sensor.connect_to_source(&self.in_front_distance_sensor);
-        self.out_velocity.connect_to_source(&velocity_control.out_velocity);   
+        velocity_control.in_front_distance_sensor.connect_to_source(&self.in_front_distance_sensor).unwrap();
+        self.out_velocity.connect_to_source(&velocity_control.out_velocity).unwrap();
         
         let turn_away = SpawnModule!(TurnAway,"CurvateControl");
-        turn_away.in_distance.connect_to_source(&self.in_front_distance_sensor);
-        turn_away.in_left_distance.connect_to_source(&self.in_left_distance_sensor);
-        turn_away.in_right_distance.connect_to_source(&self.in_right_distance_sensor);
-        self.out_turn_rate.connect_to_source(&turn_away.out_turn_rate);
+        turn_away.in_distance.connect_to_source(&self.in_front_distance_sensor).unwrap();
+        turn_away.in_left_distance.connect_to_source(&self.in_left_distance_sensor).unwrap();
+        turn_away.in_right_distance.connect_to_source(&self.in_right_distance_sensor).unwrap();
+        self.out_turn_rate.connect_to_source(&turn_away.out_turn_rate).unwrap();
         
-        self.set_characteristic_module(&mut *velocity_control);
+        self.set_characteristic_module(&mut *velocity_control).unwrap();
     }
 }
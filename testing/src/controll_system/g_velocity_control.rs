@@ -12,24 +12,18 @@ pub struct VelocityControl {
 }
 
 impl Group for VelocityControl {
-    #[spawn]
     fn init(&mut self, cycle_time: Duration, parent: &Parent) {
-        let break_on_obstacle = SpawnModule!(BreakOnObstacle, "BreakOnObstacle");
-        break_on_obstacle.in_distance.connect_to_source(&self.in_front_distance_sensor);
-
-        let constant_velocity = SpawnModule!(ConstantVelocity, "ConstantVelocity");
-        
-        let mut maximum_fusion = SpawnFusion! {
-            MaximumFusion,
-            "VelocityFusion",
-            inputs: [
+        network! {
+            module break_on_obstacle: BreakOnObstacle("BreakOnObstacle");
+            module constant_velocity: ConstantVelocity("ConstantVelocity");
+            fusion maximum_fusion: MaximumFusion("VelocityFusion") <- [
                 break_on_obstacle.out_velocity,
                 constant_velocity.out_velocity,
-            ]
-        };
-
-        self.out_velocity.connect_to_source(&maximum_fusion.output);   
+            ];
 
-        self.set_characteristic_module(&mut maximum_fusion);
+            self.in_front_distance_sensor -> break_on_obstacle.in_distance;
+            maximum_fusion.output -> self.out_velocity;
+            characteristic maximum_fusion;
+        }
     }
 }
\ No newline at end of file
@@ -3,6 +3,7 @@ use data_types::si_units::*;
 
 #[module]
 pub struct BreakOnObstacle {
+    #[parameter(min = "10 cm", max = "5 m", step = "10 cm", unit = "cm", description = "Distance below which the robot slows down")]
     pub par_min_distance: ParameterPort<Distance>,
     pub in_distance: ReceivePort<Distance>,
     pub out_velocity: SendPort<Velocity>,
@@ -3,6 +3,7 @@ use data_types::si_units::*;
 
 #[module]
 pub struct ConstantVelocity {
+    #[parameter(min = "0 m/s", max = "2 m/s", step = "0.1 m/s", description = "Velocity without obstacles")]
     pub par_velocity: ParameterPort<Velocity>,
     pub out_velocity: SendPort<Velocity>,
 }
@@ -2,17 +2,42 @@
 
 // use std::{thread::park, time::Duration};
 
+use std::time::Duration;
+
 // use data_types::prelude::*;
-// use rust_ib2c::prelude::*;
+use rust_ib2c::prelude::*;
 
 mod simulation;
 use simulation::*;
 
-// use crate::controll_system::ControlSystem;
+use crate::controll_system::ControlSystem;
 
 mod controll_system;
 
+/// Writes the behavior network of the control system to a DOT (`.dot`, `.gv`) or Mermaid (`.mmd`, `.mermaid`) file
+/// and exits without running the simulation, e.g. `cargo run -p testing -- --export-network network.dot`.
+fn export_network(path: &str) {
+    let network = BehaviorGroup::<ControlSystem>::main_group_network("MainGroup", Duration::from_millis(10));
+    match network.save(path) {
+        Ok(()) => println!("Exported the behavior network to {}", path),
+        Err(error) => {
+            eprintln!("Failed to export the behavior network to {}: {}", path, error);
+            std::process::exit(1);
+        }
+    }
+}
+
 pub fn main() {    
+    let arguments: Vec<String> = std::env::args().collect();
+    if let Some(index) = arguments.iter().position(|argument| argument == "--export-network") {
+        let Some(path) = arguments.get(index + 1) else {
+            eprintln!("Usage: testing --export-network <file.dot|file.mmd>");
+            std::process::exit(2);
+        };
+        export_network(path);
+        return;
+    }
+
     // let battery_capacity = Energy::kilowatt_hours(100.0);
     // let battery_voltage = Voltage::volts(12.0);
     // let wire_resistance = Resistance::milliohms(50.0);
@@ -15,6 +15,7 @@ pub struct Model {
     in_front_distance_sensor: InputPort<Distance>,
     in_left_distance_sensor: InputPort<Distance>,
     in_right_distance_sensor: InputPort<Distance>,
+    distance_sensors: SyncPorts,
     out_velocity: OutputPort<Velocity>,
     out_turn_rate: OutputPort<AngularVelocity>,
 
@@ -28,13 +29,22 @@ pub fn model(app: &App) -> Model {
 
     let control_system = SpawnMainGroup!(ControlSystem, "MainGroup", Duration::from_millis(10));
 
+    let in_front_distance_sensor = InputPort::from(&control_system.in_front_distance_sensor);
+    let in_left_distance_sensor = InputPort::from(&control_system.in_left_distance_sensor);
+    let in_right_distance_sensor = InputPort::from(&control_system.in_right_distance_sensor);
+    let distance_sensors = SyncPorts::new();
+    distance_sensors.add(&in_front_distance_sensor);
+    distance_sensors.add(&in_left_distance_sensor);
+    distance_sensors.add(&in_right_distance_sensor);
+
     Model {
         _window,
-        in_front_distance_sensor: InputPort::from(&control_system.in_front_distance_sensor),
+        in_front_distance_sensor,
         out_velocity: OutputPort::from(&control_system.out_velocity),
         out_turn_rate: OutputPort::from(&control_system.out_turn_rate),
-        in_left_distance_sensor: InputPort::from(&control_system.in_left_distance_sensor),
-        in_right_distance_sensor: InputPort::from(&control_system.in_right_distance_sensor),
+        in_left_distance_sensor,
+        in_right_distance_sensor,
+        distance_sensors,
         car_position: Vector2::default(),
         car_orientation: Rotation2D::from_angle(0.0),
         wall_distance_front: Distance::ZERO,
@@ -55,13 +65,14 @@ pub fn update(app: &App, model: &mut Model, update: Update) {
     let mouse_distance_right = vitrual_sensor_to_mouse_circle(model.car_position, model.car_orientation + (-1.0 * Rotation2D::from_angle(1.0)), Distance::meters(100.0), mouse_position, Distance::centimeters(50.0));
     
     let min_distance = Distance::min(wall_distance_front, mouse_distance);
-    model.in_front_distance_sensor.set(min_distance);
-    model.wall_distance_front = min_distance;
-
     let min_distance_left = Distance::min(wall_distance_left, mouse_distance_left);
-    model.in_left_distance_sensor.set(min_distance_left);
     let min_distance_right = Distance::min(wall_distance_right, mouse_distance_right);
-    model.in_right_distance_sensor.set(min_distance_right);
+    model.distance_sensors.publish(|| {
+        model.in_front_distance_sensor.set(min_distance);
+        model.in_left_distance_sensor.set(min_distance_left);
+        model.in_right_distance_sensor.set(min_distance_right);
+    });
+    model.wall_distance_front = min_distance;
     model.wall_distance_left = min_distance_left;
     
     let velocity = model.out_velocity.get_or_default();